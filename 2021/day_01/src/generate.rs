@@ -0,0 +1,27 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A sonar sweep of `size` depths drifting downwards
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut depth = rng.range(100..200);
+    (0..size)
+        .map(|_| {
+            depth = (depth + rng.range(-10..20)).max(0);
+            depth.to_string()
+        })
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let depths: Vec<u64> = input.iter().map(|s| s.parse().unwrap()).collect();
+    let increases = |values: &[u64]| {
+        (1..values.len())
+            .filter(|i| values[*i] > values[i - 1])
+            .count()
+    };
+
+    let sums: Vec<u64> = (2..depths.len())
+        .map(|i| depths[i - 2] + depths[i - 1] + depths[i])
+        .collect();
+    (increases(&depths).into(), increases(&sums).into())
+}
@@ -1,4 +1,9 @@
-fn task_one(input: &[String]) -> usize {
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+fn task_one(input: &[String]) -> Answer {
     input
         .iter()
         .map(|s| s.parse::<usize>().unwrap())
@@ -6,9 +11,10 @@ fn task_one(input: &[String]) -> usize {
         .windows(2)
         .filter(|w| w[1] > w[0])
         .count()
+        .into()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     input
         .iter()
         .map(|s| s.parse::<usize>().unwrap())
@@ -16,12 +22,21 @@ fn task_two(input: &[String]) -> usize {
         .windows(4)
         .filter(|w| w[3] > w[0])
         .count()
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -35,35 +50,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
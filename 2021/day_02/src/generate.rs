@@ -0,0 +1,48 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` submarine commands. The depth and aim never go above the surface,
+/// like in the puzzle
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut aim = 0;
+    let mut depth = 0;
+    (0..size)
+        .map(|_| {
+            let x = rng.range(1..10);
+            let command = match rng.below(3) {
+                0 => "forward",
+                1 if aim >= x && depth >= x => "up",
+                _ => "down",
+            };
+            match command {
+                "up" => (aim, depth) = (aim - x, depth - x),
+                "down" => (aim, depth) = (aim + x, depth + x),
+                _ => {}
+            }
+            format!("{} {}", command, x)
+        })
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let (mut position, mut depth, mut aim, mut aimed_depth) = (0i64, 0i64, 0i64, 0i64);
+    for line in input {
+        let (command, x) = line.split_once(' ').unwrap();
+        let x: i64 = x.parse().unwrap();
+        match command {
+            "forward" => {
+                position += x;
+                aimed_depth += aim * x;
+            }
+            "up" => {
+                depth -= x;
+                aim -= x;
+            }
+            _ => {
+                depth += x;
+                aim += x;
+            }
+        }
+    }
+    ((position * depth).into(), (position * aimed_depth).into())
+}
@@ -0,0 +1,58 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A diagnostic report of `size` distinct words, all of the same width
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let width = (usize::BITS - size.leading_zeros()) as usize + rng.below(4) + 1;
+    let mut words: Vec<u64> = Vec::new();
+    while words.len() < size.max(1) {
+        let word = rng.next_u64() >> (64 - width);
+        if !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    words
+        .iter()
+        .map(|w| format!("{:0width$b}", w, width = width))
+        .collect()
+}
+
+// Ones and zeros in column `bit` of the given lines
+fn count(lines: &[&String], bit: usize) -> (usize, usize) {
+    let ones = lines.iter().filter(|l| l.as_bytes()[bit] == b'1').count();
+    (ones, lines.len() - ones)
+}
+
+fn rating(input: &[String], keep_ones: fn(usize, usize) -> bool) -> u64 {
+    let mut lines: Vec<&String> = input.iter().collect();
+    for bit in 0..input[0].len() {
+        if lines.len() == 1 {
+            break;
+        }
+        let (ones, zeros) = count(&lines, bit);
+        // A column where every line agrees can't tell them apart
+        if ones == 0 || zeros == 0 {
+            continue;
+        }
+        let keep = if keep_ones(ones, zeros) { b'1' } else { b'0' };
+        lines.retain(|l| l.as_bytes()[bit] == keep);
+    }
+    u64::from_str_radix(lines[0], 2).unwrap()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let lines: Vec<&String> = input.iter().collect();
+    let (mut gamma, mut epsilon) = (0u64, 0u64);
+    for bit in 0..input[0].len() {
+        let (ones, zeros) = count(&lines, bit);
+        gamma = gamma * 2 + (ones > zeros) as u64;
+        epsilon = epsilon * 2 + (ones <= zeros) as u64;
+    }
+
+    let oxygen = rating(input, |ones, zeros| ones >= zeros);
+    let scrubber = rating(input, |ones, zeros| ones < zeros);
+    (
+        (gamma as u128 * epsilon as u128).into(),
+        (oxygen as u128 * scrubber as u128).into(),
+    )
+}
@@ -1,74 +1,146 @@
-fn generator_rating<F>(vec: &[String], f: &F) -> usize
-where
-    F: Fn(usize, usize) -> bool,
-{
-    let mut ones: Vec<String> = Vec::new();
-    let mut zeros: Vec<String> = Vec::new();
-    let mut res = vec.to_owned();
-    for b in 0..vec[0].len() {
-        for v in &res {
-            if v.chars().nth(b).unwrap() == '1' {
-                ones.push(v.to_string());
-            } else {
-                zeros.push(v.to_string());
-            }
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+// Which bit a rating keeps when both halves are equally common
+#[derive(Debug, Clone, Copy)]
+enum TieBreak {
+    Zero,
+    One,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Criterion {
+    MostCommon(TieBreak),
+    LeastCommon(TieBreak),
+}
+
+struct Report {
+    // Sorted, so every prefix selects a contiguous range of words
+    words: Vec<u64>,
+    width: u32,
+}
+
+impl TieBreak {
+    fn from_env(name: &str, default: TieBreak) -> TieBreak {
+        match std::env::var(name).as_deref() {
+            Ok("0") => TieBreak::Zero,
+            Ok("1") => TieBreak::One,
+            Ok(v) => panic!("{name} must be 0 or 1, got {v}"),
+            Err(_) => default,
         }
-        if ones.is_empty() || zeros.is_empty() {
-            break;
-        };
-        res = if f(ones.len(), zeros.len()) {
-            ones.clone()
-        } else {
-            zeros.clone()
-        };
-        ones.clear();
-        zeros.clear();
     }
-    usize::from_str_radix(res[0].as_str(), 2).unwrap()
 }
 
-fn to_i32(slice: &[usize]) -> usize {
-    slice
-        .iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (i, &b)| acc + 2_usize.pow(i as u32) * b)
+impl Criterion {
+    fn keep_ones(&self, ones: usize, zeros: usize) -> bool {
+        match (self, ones.cmp(&zeros)) {
+            (
+                Criterion::MostCommon(tie) | Criterion::LeastCommon(tie),
+                std::cmp::Ordering::Equal,
+            ) => {
+                matches!(tie, TieBreak::One)
+            }
+            (Criterion::MostCommon(_), ordering) => ordering.is_gt(),
+            (Criterion::LeastCommon(_), ordering) => ordering.is_lt(),
+        }
+    }
 }
 
-fn task_one(input: &[String]) -> usize {
-    let rows = input.len() / 2;
-    let length = input[0].len();
-    let mut bits: Vec<usize> = vec![0; length];
-    let mut flipped: Vec<usize> = vec![0; length];
+impl Report {
+    fn new(input: &[String]) -> Self {
+        let width = input.iter().map(|v| v.len()).max().unwrap_or(0) as u32;
+        assert!(width <= u64::BITS, "words wider than {} bits", u64::BITS);
+        let mut words: Vec<u64> = input
+            .iter()
+            .map(|v| u64::from_str_radix(v, 2).unwrap())
+            .collect();
+        words.sort_unstable();
+        Self { words, width }
+    }
 
-    for v in input {
-        for (i, c) in v.chars().enumerate() {
-            bits[i] += c.to_digit(10).unwrap() as usize;
-        }
+    fn mask(&self) -> u64 {
+        u64::MAX.checked_shr(u64::BITS - self.width).unwrap_or(0)
     }
 
-    for i in 0..bits.len() {
-        bits[i] = if bits[i] > rows { 1 } else { 0 };
-        flipped[i] = if bits[i] == 1 { 0 } else { 1 };
+    // Number of set bits per column, most significant column first
+    fn column_ones(&self) -> Vec<usize> {
+        (0..self.width)
+            .rev()
+            .map(|b| self.words.iter().filter(|w| *w >> b & 1 == 1).count())
+            .collect()
     }
 
-    let gamma = to_i32(&bits);
-    let epsilon = to_i32(&flipped);
+    fn select(&self, criterion: Criterion) -> u64 {
+        self.column_ones().iter().fold(0, |acc, ones| {
+            let bit = criterion.keep_ones(*ones, self.words.len() - ones);
+            acc << 1 | bit as u64
+        })
+    }
+
+    // Narrows the sorted words one column at a time. Within the current
+    // range every word shares the prefix so far, so the words with a zero
+    // in the next column all come before the ones
+    fn rating(&self, criterion: Criterion) -> u64 {
+        let mut range = &self.words[..];
+        for b in (0..self.width).rev() {
+            if range.len() <= 1 {
+                break;
+            }
+            let split = range.partition_point(|w| *w >> b & 1 == 0);
+            let (zeros, ones) = range.split_at(split);
+            if zeros.is_empty() || ones.is_empty() {
+                continue;
+            }
+            range = if criterion.keep_ones(ones.len(), zeros.len()) {
+                ones
+            } else {
+                zeros
+            };
+        }
+        range[0]
+    }
+}
+
+fn task_one(input: &[String]) -> Answer {
+    let report = Report::new(input);
+    let gamma = report.select(Criterion::MostCommon(TieBreak::from_env(
+        "GAMMATIE",
+        TieBreak::Zero,
+    )));
+    let epsilon = !gamma & report.mask();
 
-    gamma * epsilon
+    // Words can be up to 64 bits wide, so the product needs twice that
+    (gamma as u128 * epsilon as u128).into()
 }
 
-fn task_two(input: &[String]) -> usize {
-    let gamma = generator_rating(input, &|a, b| a >= b);
-    let epsilon = generator_rating(input, &|a, b| a < b);
+fn task_two(input: &[String]) -> Answer {
+    let report = Report::new(input);
+    let oxygen = report.rating(Criterion::MostCommon(TieBreak::from_env(
+        "OXYGENTIE",
+        TieBreak::One,
+    )));
+    let scrubber = report.rating(Criterion::LeastCommon(TieBreak::from_env(
+        "SCRUBBERTIE",
+        TieBreak::Zero,
+    )));
 
-    gamma * epsilon
+    (oxygen as u128 * scrubber as u128).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -82,35 +154,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
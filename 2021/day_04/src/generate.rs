@@ -0,0 +1,64 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const SIDE: usize = 5;
+
+/// `size` bingo boards and a draw order that calls every number on them
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let numbers = (SIDE * SIDE * (size + 3) / 2).max(SIDE * SIDE);
+    let mut draws: Vec<usize> = (0..numbers).collect();
+    rng.shuffle(&mut draws);
+
+    let mut lines = vec![draws
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(",")];
+    for _ in 0..size.max(1) {
+        let mut cells: Vec<usize> = (0..numbers).collect();
+        rng.shuffle(&mut cells);
+        lines.push(String::new());
+        lines.extend(cells[..SIDE * SIDE].chunks(SIDE).map(|row| {
+            row.iter()
+                .map(|n| format!("{:>2}", n))
+                .collect::<Vec<_>>()
+                .join(" ")
+        }));
+    }
+    lines
+}
+
+// Plays the game one draw at a time, marking every board
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let draws: Vec<usize> = input[0].split(',').map(|n| n.parse().unwrap()).collect();
+    let mut boards: Vec<Vec<Option<usize>>> = input[1..]
+        .chunks(SIDE + 1)
+        .map(|chunk| {
+            chunk[1..]
+                .iter()
+                .flat_map(|row| row.split_whitespace().map(|n| n.parse().ok()))
+                .collect()
+        })
+        .collect();
+
+    let mut won = vec![false; boards.len()];
+    let mut scores = Vec::new();
+    for draw in draws {
+        for (board, won) in boards.iter_mut().zip(won.iter_mut()) {
+            for cell in board.iter_mut() {
+                if *cell == Some(draw) {
+                    *cell = None;
+                }
+            }
+            let complete = (0..SIDE).any(|i| {
+                (0..SIDE).all(|j| board[i * SIDE + j].is_none())
+                    || (0..SIDE).all(|j| board[j * SIDE + i].is_none())
+            });
+            if complete && !*won {
+                *won = true;
+                scores.push(board.iter().flatten().sum::<usize>() * draw);
+            }
+        }
+    }
+    (scores[0].into(), scores[scores.len() - 1].into())
+}
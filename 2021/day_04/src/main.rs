@@ -1,3 +1,10 @@
+use std::collections::HashMap;
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Clone, Debug)]
 struct Bingo {
     numbers: Vec<usize>,
@@ -6,49 +13,114 @@ struct Bingo {
 
 #[derive(Clone, Debug)]
 struct Board {
-    board: Vec<Vec<Position>>,
+    rows: usize,
+    cols: usize,
+    cells: Vec<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Line {
+    Row(usize),
+    Column(usize),
+    Diagonal,
+    AntiDiagonal,
 }
 
 #[derive(Clone, Debug)]
-struct Position {
+struct Win {
+    board: usize,
+    draw: usize,
     number: usize,
-    hit: bool,
+    line: Line,
+    score: usize,
 }
 
 impl Board {
-    fn has_bingo(&self) -> bool {
-        for i in 0..5 {
-            let bingo =
-                (0..5).all(|j| self.board[i][j].hit) || (0..5).all(|j| self.board[j][i].hit);
-            if bingo {
-                return true;
-            }
+    fn lines(&self, diagonals: bool) -> Vec<(Line, Vec<usize>)> {
+        let mut lines: Vec<_> = (0..self.rows)
+            .map(|r| {
+                (
+                    Line::Row(r),
+                    (0..self.cols).map(|c| r * self.cols + c).collect(),
+                )
+            })
+            .chain((0..self.cols).map(|c| {
+                (
+                    Line::Column(c),
+                    (0..self.rows).map(|r| r * self.cols + c).collect(),
+                )
+            }))
+            .collect();
+
+        if diagonals && self.rows == self.cols {
+            let n = self.rows;
+            lines.push((Line::Diagonal, (0..n).map(|i| i * n + i).collect()));
+            lines.push((
+                Line::AntiDiagonal,
+                (0..n).map(|i| i * n + n - 1 - i).collect(),
+            ));
         }
-        false
+        lines
     }
 
-    fn get_sum(&self) -> usize {
-        let mut sum = 0;
-        for i in 0..5 {
-            for j in 0..5 {
-                if !self.board[j][i].hit {
-                    sum += self.board[j][i].number;
-                }
-            }
-        }
-        sum
+    // A line completes on the draw of its latest number, and the board wins on
+    // the earliest completed line
+    fn winning_turn(
+        &self,
+        turns: &HashMap<usize, usize>,
+        diagonals: bool,
+    ) -> Option<(usize, Line)> {
+        self.lines(diagonals)
+            .into_iter()
+            .filter_map(|(line, cells)| {
+                cells
+                    .iter()
+                    .map(|cell| turns.get(&self.cells[*cell]).copied())
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|draws| draws.into_iter().max())
+                    .map(|draw| (draw, line))
+            })
+            .min_by_key(|(draw, _)| *draw)
     }
 
-    fn mark_number(&mut self, number: usize) -> bool {
-        for row in &mut self.board {
-            for n in row {
-                if number == n.number {
-                    n.hit = true;
-                    return true;
-                }
-            }
+    fn get_sum(&self, turns: &HashMap<usize, usize>, draw: usize) -> usize {
+        self.cells
+            .iter()
+            .filter(|number| turns.get(number).is_none_or(|turn| *turn > draw))
+            .sum()
+    }
+}
+
+impl Bingo {
+    fn turns(&self) -> HashMap<usize, usize> {
+        let mut turns = HashMap::new();
+        for (draw, number) in self.numbers.iter().enumerate() {
+            turns.entry(*number).or_insert(draw);
         }
-        false
+        turns
+    }
+
+    // Every board that eventually wins, in the order the wins are called out
+    fn wins(&self, diagonals: bool) -> Vec<Win> {
+        let turns = self.turns();
+        let mut wins: Vec<_> = self
+            .boards
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, board)| {
+                let (draw, line) = board.winning_turn(&turns, diagonals)?;
+                let number = self.numbers[draw];
+                Some(Win {
+                    board: idx,
+                    draw,
+                    number,
+                    line,
+                    score: board.get_sum(&turns, draw) * number,
+                })
+            })
+            .collect();
+        wins.sort_by_key(|win| (win.draw, win.board));
+        wins
     }
 }
 
@@ -58,71 +130,64 @@ fn get_bingo_boards(vec: &[String]) -> Bingo {
         .map(|x| x.parse::<usize>().unwrap())
         .collect();
 
-    let mut boards: Vec<Board> = Vec::new();
-    let mut board = Vec::new();
-    for v in vec.iter().skip(2) {
-        let row: Vec<Position> = v
-            .split_whitespace()
-            .map(|c| Position {
-                number: c.parse::<usize>().unwrap(),
-                hit: false,
-            })
-            .collect();
-        if !row.is_empty() {
-            board.push(row.clone());
-        }
-        if board.len() == 5 {
-            boards.push(Board {
-                board: board.clone(),
-            });
-            board.clear();
-        }
-    }
+    let boards = vec[1..]
+        .split(|s| s.trim().is_empty())
+        .filter(|rows| !rows.is_empty())
+        .map(|rows| {
+            let cells: Vec<usize> = rows
+                .iter()
+                .flat_map(|row| row.split_whitespace().map(|c| c.parse::<usize>().unwrap()))
+                .collect();
+            Board {
+                rows: rows.len(),
+                cols: cells.len() / rows.len(),
+                cells,
+            }
+        })
+        .collect();
+
     Bingo { numbers, boards }
 }
 
-fn task_one(input: &[String]) -> usize {
-    let mut bingo = get_bingo_boards(input);
-    for number in bingo.numbers {
-        for board in &mut bingo.boards {
-            if !board.mark_number(number) {
-                continue;
-            }
-            if board.has_bingo() {
-                return board.get_sum() * number;
-            }
+fn get_wins(input: &[String]) -> Vec<Win> {
+    let bingo = get_bingo_boards(input);
+    let wins = bingo.wins(std::env::var("DIAGONALS").is_ok());
+    if std::env::var("EVENTS").is_ok() {
+        for (rank, win) in wins.iter().enumerate() {
+            println!(
+                "#{}: board {} won on draw {} ({}) with {:?}, score {}",
+                rank + 1,
+                win.board,
+                win.draw + 1,
+                win.number,
+                win.line,
+                win.score
+            );
         }
     }
-    unreachable!();
+    wins
 }
 
-fn task_two(input: &[String]) -> usize {
-    let mut bingo = get_bingo_boards(input);
-    for number in bingo.numbers {
-        let mut remaining = Vec::new();
-        let len = bingo.boards.len();
-
-        for mut board in bingo.boards.into_iter() {
-            if !board.mark_number(number) {
-                remaining.push(board);
-                continue;
-            }
+fn task_one(input: &[String]) -> Answer {
+    get_wins(input).first().unwrap().score.into()
+}
 
-            if !board.has_bingo() {
-                remaining.push(board);
-            } else if len == 1 {
-                return board.get_sum() * number;
-            }
-        }
-        bingo.boards = remaining;
-    }
-    unreachable!();
+fn task_two(input: &[String]) -> Answer {
+    get_wins(input).last().unwrap().score.into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -136,35 +201,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,63 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` vents that are horizontal, vertical or at 45 degrees
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let extent = 2 * size as i64 + 10;
+    (0..size)
+        .map(|_| {
+            let (x1, y1) = (rng.range(0..extent), rng.range(0..extent));
+            let length = rng.range(0..extent / 2 + 1);
+            let (dx, dy) = *rng.pick(&[
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (1, -1),
+                (-1, 0),
+                (0, -1),
+                (-1, -1),
+                (-1, 1),
+            ]);
+            let (x2, y2) = (x1 + dx * length, y1 + dy * length);
+            // Keep the far end inside the grid
+            let fits = |v: i64| (0..extent).contains(&v);
+            let (x2, y2) = if fits(x2) && fits(y2) {
+                (x2, y2)
+            } else {
+                (x1, y1)
+            };
+            format!("{},{} -> {},{}", x1, y1, x2, y2)
+        })
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let lines: Vec<Vec<i64>> = input
+        .iter()
+        .map(|l| {
+            l.split(" -> ")
+                .flat_map(|p| p.split(','))
+                .map(|v| v.parse().unwrap())
+                .collect()
+        })
+        .collect();
+    let extent = lines.iter().flatten().max().map_or(0, |m| *m as usize + 1);
+
+    let count = |diagonals: bool| {
+        let mut grid = vec![vec![0; extent]; extent];
+        for line in &lines {
+            let (x1, y1, x2, y2) = (line[0], line[1], line[2], line[3]);
+            if x1 != x2 && y1 != y2 && !diagonals {
+                continue;
+            }
+            let steps = (x2 - x1).abs().max((y2 - y1).abs());
+            for i in 0..=steps {
+                let x = x1 + i * (x2 - x1).signum();
+                let y = y1 + i * (y2 - y1).signum();
+                grid[y as usize][x as usize] += 1;
+            }
+        }
+        grid.iter().flatten().filter(|c| **c >= 2).count()
+    };
+    (count(false).into(), count(true).into())
+}
@@ -1,132 +1,276 @@
-#[derive(Debug, Clone)]
+use std::collections::{HashMap, HashSet};
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+#[derive(Debug, Clone, Copy)]
 struct Line {
-    x1: usize,
-    y1: usize,
-    x2: usize,
-    y2: usize,
+    start: (i64, i64),
+    end: (i64, i64),
 }
 
-#[derive(Debug, Clone)]
-struct List {
-    list: Vec<Line>,
-    diagram: Vec<Vec<i32>>,
+// A line through lattice points, stepping by the primitive direction `step`.
+// `offset` tells parallel lines apart, and points along it are ordered by
+// `dot`, which grows by `|step|^2` for every lattice step
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct Carrier {
+    step: (i64, i64),
+    offset: i64,
 }
 
-fn get_list(vec: &[String]) -> List {
-    let mut list: Vec<Line> = Vec::new();
-    let mut size: usize = 0;
-    for v in vec {
-        let (start, end) = v.split_once("->").unwrap();
-        let (x1, y1) = start.trim().split_once(',').unwrap();
-        let (x2, y2) = end.trim().split_once(',').unwrap();
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
 
-        let x1 = x1.parse::<usize>().unwrap();
-        let y1 = y1.parse::<usize>().unwrap();
-        let x2 = x2.parse::<usize>().unwrap();
-        let y2 = y2.parse::<usize>().unwrap();
+fn get_lines(vec: &[String]) -> Vec<Line> {
+    let point = |s: &str| {
+        let (x, y) = s.trim().split_once(',').unwrap();
+        (x.parse().unwrap(), y.parse().unwrap())
+    };
+    vec.iter()
+        .map(|v| {
+            let (start, end) = v.split_once("->").unwrap();
+            Line {
+                start: point(start),
+                end: point(end),
+            }
+        })
+        .collect()
+}
 
-        size = if x1 > size { x1 } else { size };
-        size = if y1 > size { y1 } else { size };
-        size = if x2 > size { x2 } else { size };
-        size = if y2 > size { y2 } else { size };
+impl Line {
+    fn is_straight(&self) -> bool {
+        self.start.0 == self.end.0 || self.start.1 == self.end.1
+    }
 
-        list.push(Line { x1, y1, x2, y2 })
+    // Bresenham, which for straight and 45 degree lines hits exactly the
+    // points on the line. Other slopes mark the points closest to the line,
+    // most of which are not on it
+    fn rasterise(&self) -> impl Iterator<Item = (i64, i64)> {
+        let (x1, y1) = self.end;
+        let (dx, dy) = ((x1 - self.start.0).abs(), -(y1 - self.start.1).abs());
+        let (sx, sy) = ((x1 - self.start.0).signum(), (y1 - self.start.1).signum());
+        let mut state = Some((self.start, dx + dy));
+        std::iter::from_fn(move || {
+            let ((x, y), err) = state?;
+            state = ((x, y) != (x1, y1)).then(|| {
+                let (mut x, mut y, mut err) = (x, y, err);
+                let e2 = 2 * err;
+                if e2 >= dy {
+                    err += dy;
+                    x += sx;
+                }
+                if e2 <= dx {
+                    err += dx;
+                    y += sy;
+                }
+                ((x, y), err)
+            });
+            Some((x, y))
+        })
     }
-    List {
-        list,
-        diagram: vec![vec![0; size + 1]; size + 1],
+
+    fn carrier(&self) -> Carrier {
+        let (dx, dy) = (self.end.0 - self.start.0, self.end.1 - self.start.1);
+        // A single point can sit on a carrier of any direction, so just pick one
+        let (mut sx, mut sy) = match gcd(dx, dy) {
+            0 => (1, 0),
+            g => (dx / g, dy / g),
+        };
+        if sx < 0 || (sx == 0 && sy < 0) {
+            (sx, sy) = (-sx, -sy);
+        }
+        Carrier {
+            step: (sx, sy),
+            offset: sx * self.start.1 - sy * self.start.0,
+        }
     }
-}
 
-fn is_straight(line: &Line) -> bool {
-    line.x1 == line.x2 || line.y1 == line.y2
-}
+    fn span(&self, carrier: &Carrier) -> (i64, i64) {
+        let (a, b) = (carrier.dot(self.start), carrier.dot(self.end));
+        (a.min(b), a.max(b))
+    }
 
-fn is_diagonal(line: &Line) -> bool {
-    (line.x1 + line.y2) == (line.x2 + line.y1) || (line.x1 + line.y1) == (line.x2 + line.y2)
-}
+    fn contains(&self, (x, y): (i64, i64)) -> bool {
+        let (x0, y0) = self.start;
+        let (x1, y1) = self.end;
+        (x1 - x0) * (y - y0) == (y1 - y0) * (x - x0)
+            && x0.min(x1) <= x
+            && x <= x0.max(x1)
+            && y0.min(y1) <= y
+            && y <= y0.max(y1)
+    }
 
-fn update_straight(diagram: &mut [Vec<i32>], line: &Line) {
-    if line.x1 == line.x2 {
-        let (start, end) = if line.y1 < line.y2 {
-            (line.y1, line.y2)
-        } else {
-            (line.y2, line.y1)
-        };
-        for y in start..=end {
-            diagram[line.x1][y] += 1;
+    // The single lattice point where two non-parallel segments meet, if any
+    fn crossing(&self, other: &Line) -> Option<(i64, i64)> {
+        let (d1, d2) = (
+            (self.end.0 - self.start.0, self.end.1 - self.start.1),
+            (other.end.0 - other.start.0, other.end.1 - other.start.1),
+        );
+        if d1 == (0, 0) || d2 == (0, 0) {
+            let point = if d1 == (0, 0) {
+                self.start
+            } else {
+                other.start
+            };
+            return (self.contains(point) && other.contains(point)).then_some(point);
         }
-    } else if line.y1 == line.y2 {
-        let (start, end) = if line.x1 < line.x2 {
-            (line.x1, line.x2)
-        } else {
-            (line.x2, line.x1)
-        };
-        (start..=end).for_each(|x| {
-            diagram[x][line.y1] += 1;
-        });
-    }
-}
-fn update_diagnoal(diagram: &mut [Vec<i32>], line: &Line) {
-    if line.x1 > line.x2 {
-        let length = line.x1 - line.x2;
-        if (line.x1 + line.y2) == (line.x2 + line.y1) {
-            for i in 0..=length {
-                let x = line.x2 + i;
-                let y = line.y2 + i;
-                diagram[x][y] += 1;
-            }
-        } else {
-            for i in 0..=length {
-                let x = line.x1 - i;
-                let y = line.y1 + i;
-                diagram[x][y] += 1;
-            }
+        let denom = d1.0 * d2.1 - d1.1 * d2.0;
+        if denom == 0 {
+            return None;
         }
-    } else {
-        let length = line.x2 - line.x1;
-        if (line.x1 + line.y2) == (line.x2 + line.y1) {
-            for i in 0..=length {
-                let x = line.x1 + i;
-                let y = line.y1 + i;
-                diagram[x][y] += 1;
-            }
-        } else {
-            for i in 0..=length {
-                let x = line.x1 + i;
-                let y = line.y1 - i;
-                diagram[x][y] += 1;
-            }
+        let (wx, wy) = (other.start.0 - self.start.0, other.start.1 - self.start.1);
+        let num = wx * d2.1 - wy * d2.0;
+        let (x, y) = (
+            self.start.0 as i128 * denom as i128 + num as i128 * d1.0 as i128,
+            self.start.1 as i128 * denom as i128 + num as i128 * d1.1 as i128,
+        );
+        if x % denom as i128 != 0 || y % denom as i128 != 0 {
+            return None;
         }
+        let point = ((x / denom as i128) as i64, (y / denom as i128) as i64);
+        (self.contains(point) && other.contains(point)).then_some(point)
     }
 }
 
-fn task_one(input: &[String]) -> usize {
-    let mut list = get_list(input);
-    for line in &list.list {
-        if is_straight(line) {
-            update_straight(&mut list.diagram, line);
+impl Carrier {
+    fn dot(&self, (x, y): (i64, i64)) -> i64 {
+        self.step.0 * x + self.step.1 * y
+    }
+
+    fn stride(&self) -> i64 {
+        self.dot(self.step)
+    }
+
+    fn through(&self, (x, y): (i64, i64)) -> Carrier {
+        Carrier {
+            step: self.step,
+            offset: self.step.0 * y - self.step.1 * x,
+        }
+    }
+}
+
+fn count_rasterised(lines: &[Line]) -> usize {
+    let mut counts: HashMap<(i64, i64), u32> = HashMap::new();
+    for line in lines {
+        for point in line.rasterise() {
+            *counts.entry(point).or_default() += 1;
         }
     }
-    list.diagram.iter().flatten().filter(|&&x| x >= 2).count()
+    counts.values().filter(|count| **count >= 2).count()
 }
 
-fn task_two(input: &[String]) -> usize {
-    let mut list = get_list(input);
-    for line in list.list {
-        if is_straight(&line) {
-            update_straight(&mut list.diagram, &line);
-        } else if is_diagonal(&line) {
-            update_diagnoal(&mut list.diagram, &line);
+// Where at least two spans on the same carrier overlap, merged
+fn double_spans(mut spans: Vec<(i64, i64)>) -> Vec<(i64, i64)> {
+    spans.sort_unstable();
+    let mut doubles: Vec<(i64, i64)> = Vec::new();
+    let mut reach = i64::MIN;
+    for (start, end) in spans {
+        if start <= reach {
+            let overlap = (start, end.min(reach));
+            match doubles.last_mut() {
+                Some(last) if overlap.0 <= last.1 => last.1 = last.1.max(overlap.1),
+                _ => doubles.push(overlap),
+            }
         }
+        reach = reach.max(end);
     }
-    list.diagram.iter().flatten().filter(|&&x| x >= 2).count()
+    doubles
+}
+
+// Counts without visiting every point: collinear overlaps are measured as
+// intervals, and the remaining overlaps are single crossing points. Only
+// lattice points exactly on a line count. For straight and 45 degree lines
+// those are the points the rasteriser marks, but for any other slope it also
+// marks the points closest to the line, so the two modes count differently
+fn count_analytic(lines: &[Line]) -> usize {
+    let mut carriers: HashMap<Carrier, Vec<(i64, i64)>> = HashMap::new();
+    for line in lines {
+        let carrier = line.carrier();
+        carriers
+            .entry(carrier)
+            .or_default()
+            .push(line.span(&carrier));
+    }
+    let doubles: HashMap<Carrier, Vec<(i64, i64)>> = carriers
+        .into_iter()
+        .map(|(carrier, spans)| (carrier, double_spans(spans)))
+        .collect();
+
+    let mut count: i64 = doubles
+        .iter()
+        .flat_map(|(carrier, spans)| spans.iter().map(|(a, b)| (b - a) / carrier.stride() + 1))
+        .sum();
+
+    let crossings: HashSet<_> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, a)| lines[i + 1..].iter().filter_map(|b| a.crossing(b)))
+        .collect();
+    let steps: HashSet<_> = doubles.keys().map(|carrier| carrier.step).collect();
+
+    // A crossing can already be part of one or more collinear overlaps, and
+    // should only be counted once in total
+    for point in crossings {
+        let covering = steps
+            .iter()
+            .map(|step| {
+                Carrier {
+                    step: *step,
+                    offset: 0,
+                }
+                .through(point)
+            })
+            .filter(|carrier| {
+                let t = carrier.dot(point);
+                doubles
+                    .get(carrier)
+                    .is_some_and(|spans| spans.iter().any(|(a, b)| (*a..=*b).contains(&t)))
+            })
+            .count() as i64;
+        count += 1 - covering.min(1) - (covering - 1).max(0);
+    }
+    count as usize
+}
+
+fn count_overlaps(lines: &[Line]) -> usize {
+    if std::env::var("ANALYTIC").is_ok() {
+        count_analytic(lines)
+    } else {
+        count_rasterised(lines)
+    }
+}
+
+fn task_one(input: &[String]) -> Answer {
+    let lines: Vec<_> = get_lines(input)
+        .into_iter()
+        .filter(|line| line.is_straight())
+        .collect();
+    count_overlaps(&lines).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    count_overlaps(&get_lines(input)).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -140,35 +284,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
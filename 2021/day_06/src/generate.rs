@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A school of `size` lanternfish timers
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let timers: Vec<String> = (0..size.max(1))
+        .map(|_| rng.range(1..6).to_string())
+        .collect();
+    vec![timers.join(",")]
+}
+
+// Fish that a single fish with `timer` turns into after `days`, following
+// every birth instead of bucketing fish by timer
+fn school(timer: u64, days: u64, memo: &mut HashMap<(u64, u64), u64>) -> u64 {
+    if days <= timer {
+        return 1;
+    }
+    if let Some(count) = memo.get(&(timer, days)) {
+        return *count;
+    }
+    let left = days - timer - 1;
+    let count = school(6, left, memo) + school(8, left, memo);
+    memo.insert((timer, days), count);
+    count
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let timers: Vec<u64> = input[0].split(',').map(|t| t.parse().unwrap()).collect();
+
+    let mut fish = timers.clone();
+    for _ in 0..80 {
+        let born = fish.iter().filter(|t| **t == 0).count();
+        for timer in fish.iter_mut() {
+            *timer = if *timer == 0 { 6 } else { *timer - 1 };
+        }
+        fish.extend(std::iter::repeat_n(8, born));
+    }
+
+    let mut memo = HashMap::new();
+    let long: u64 = timers.iter().map(|t| school(*t, 256, &mut memo)).sum();
+    (fish.len().into(), long.into())
+}
@@ -0,0 +1,26 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` crab positions
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let spread = 2 * size as i64 + 10;
+    let crabs: Vec<String> = (0..size.max(1))
+        .map(|_| rng.range(0..spread).to_string())
+        .collect();
+    vec![crabs.join(",")]
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let crabs: Vec<i64> = input[0].split(',').map(|c| c.parse().unwrap()).collect();
+    let (min, max) = (*crabs.iter().min().unwrap(), *crabs.iter().max().unwrap());
+    let cheapest = |cost: &dyn Fn(i64) -> i64| {
+        (min..=max)
+            .map(|target| crabs.iter().map(|c| cost((c - target).abs())).sum::<i64>())
+            .min()
+            .unwrap()
+    };
+    (
+        cheapest(&|d| d).into(),
+        cheapest(&|d| d * (d + 1) / 2).into(),
+    )
+}
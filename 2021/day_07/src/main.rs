@@ -1,3 +1,8 @@
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 fn median(vec: &mut [i32]) -> i32 {
     vec.sort();
     let mid = vec.len() / 2;
@@ -21,16 +26,16 @@ fn parse(input: &[String]) -> Vec<i32> {
         .collect()
 }
 
-fn task_one(input: &[String]) -> usize {
+fn task_one(input: &[String]) -> Answer {
     let mut vec = parse(input);
     let med = median(&mut vec);
     let mut sum = 0;
     vec.iter()
         .for_each(|&x| sum += (med - x).unsigned_abs() as usize);
-    sum
+    sum.into()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     let vec = parse(input);
 
     let mean_ceil = mean(&vec).ceil() as i32;
@@ -44,13 +49,21 @@ fn task_two(input: &[String]) -> usize {
         sum_ceil += divergent((mean_ceil - x).unsigned_abs() as usize);
     });
 
-    std::cmp::min(sum_ceil, sum_floor)
+    std::cmp::min(sum_ceil, sum_floor).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -64,35 +77,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
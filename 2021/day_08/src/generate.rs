@@ -0,0 +1,81 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const DIGITS: [&str; 10] = [
+    "abcefg", "cf", "acdeg", "acdfg", "bcdf", "abdfg", "abdefg", "acf", "abcdefg", "abcdfg",
+];
+
+fn scramble(rng: &mut Rng, wiring: &[u8], digit: usize) -> String {
+    let mut wires: Vec<u8> = DIGITS[digit]
+        .bytes()
+        .map(|s| wiring[(s - b'a') as usize])
+        .collect();
+    rng.shuffle(&mut wires);
+    String::from_utf8(wires).unwrap()
+}
+
+/// `size` displays, each with its wires scrambled differently
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let mut wiring: Vec<u8> = (b'a'..=b'g').collect();
+            rng.shuffle(&mut wiring);
+
+            let mut order: Vec<usize> = (0..10).collect();
+            rng.shuffle(&mut order);
+            let patterns: Vec<String> = order.iter().map(|d| scramble(rng, &wiring, *d)).collect();
+            let output: Vec<String> = (0..4)
+                .map(|_| {
+                    let digit = rng.below(10);
+                    scramble(rng, &wiring, digit)
+                })
+                .collect();
+            format!("{} | {}", patterns.join(" "), output.join(" "))
+        })
+        .collect()
+}
+
+fn permutations(items: Vec<u8>) -> Vec<Vec<u8>> {
+    if items.len() <= 1 {
+        return vec![items];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            let mut rest = items.clone();
+            let first = rest.remove(i);
+            permutations(rest).into_iter().map(move |mut p| {
+                p.insert(0, first);
+                p
+            })
+        })
+        .collect()
+}
+
+// Tries every wiring until all ten patterns read as digits
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let wirings = permutations((b'a'..=b'g').collect());
+    let read = |wiring: &[u8], pattern: &str| {
+        let mut segments: Vec<u8> = pattern
+            .bytes()
+            .map(|w| b'a' + wiring.iter().position(|x| *x == w).unwrap() as u8)
+            .collect();
+        segments.sort_unstable();
+        DIGITS.iter().position(|d| d.as_bytes() == segments)
+    };
+
+    let (mut easy, mut sum) = (0, 0);
+    for line in input {
+        let (patterns, output) = line.split_once(" | ").unwrap();
+        let wiring = wirings
+            .iter()
+            .find(|w| patterns.split(' ').all(|p| read(w, p).is_some()))
+            .unwrap();
+        let digits: Vec<usize> = output
+            .split(' ')
+            .map(|p| read(wiring, p).unwrap())
+            .collect();
+        easy += digits.iter().filter(|d| [1, 4, 7, 8].contains(*d)).count();
+        sum += digits.iter().fold(0, |acc, d| acc * 10 + d);
+    }
+    (easy.into(), sum.into())
+}
@@ -1,145 +1,169 @@
-use std::{
-    collections::HashSet,
-    ops::{Index, IndexMut},
-};
+use std::str::FromStr;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+const SEVEN_SEGMENT: &str = "0: abcefg
+1: cf
+2: acdeg
+3: acdfg
+4: bcdf
+5: abdfg
+6: abdefg
+7: acf
+8: abcdefg
+9: abcdfg";
+
+// Wires and segments are both identified by their letter, `a` being bit 0
 #[derive(Debug, Clone)]
-struct Entry {
-    pattern: Vec<String>,
-    output: Vec<String>,
+struct Font {
+    segments: usize,
+    glyphs: Vec<(char, u32)>,
 }
 
 #[derive(Debug, Clone)]
-struct Numbers {
-    zero: HashSet<char>,
-    one: HashSet<char>,
-    two: HashSet<char>,
-    three: HashSet<char>,
-    four: HashSet<char>,
-    five: HashSet<char>,
-    six: HashSet<char>,
-    seven: HashSet<char>,
-    eight: HashSet<char>,
-    nine: HashSet<char>,
+struct Entry {
+    pattern: Vec<u32>,
+    output: Vec<u32>,
 }
 
-impl Numbers {
-    fn new() -> Self {
-        Self {
-            zero: HashSet::new(),
-            one: HashSet::new(),
-            two: HashSet::new(),
-            three: HashSet::new(),
-            four: HashSet::new(),
-            five: HashSet::new(),
-            six: HashSet::new(),
-            seven: HashSet::new(),
-            eight: HashSet::new(),
-            nine: HashSet::new(),
-        }
-    }
+#[derive(Debug)]
+enum Decoding {
+    Unique(Vec<usize>),
+    Ambiguous,
+    Inconsistent,
 }
 
-impl Index<i32> for Numbers {
-    type Output = HashSet<char>;
-    fn index(&self, idx: i32) -> &Self::Output {
-        match idx {
-            0 => &self.zero,
-            1 => &self.one,
-            2 => &self.two,
-            3 => &self.three,
-            4 => &self.four,
-            5 => &self.five,
-            6 => &self.six,
-            7 => &self.seven,
-            8 => &self.eight,
-            9 => &self.nine,
-            _ => unreachable!(),
-        }
-    }
+fn to_mask(s: &str) -> u32 {
+    s.bytes().fold(0, |acc, b| acc | 1 << (b - b'a'))
 }
 
-impl IndexMut<i32> for Numbers {
-    fn index_mut(&mut self, idx: i32) -> &mut Self::Output {
-        match idx {
-            0 => &mut self.zero,
-            1 => &mut self.one,
-            2 => &mut self.two,
-            3 => &mut self.three,
-            4 => &mut self.four,
-            5 => &mut self.five,
-            6 => &mut self.six,
-            7 => &mut self.seven,
-            8 => &mut self.eight,
-            9 => &mut self.nine,
-            _ => unreachable!(),
-        }
+impl FromStr for Font {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let glyphs: Vec<(char, u32)> = s
+            .lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| {
+                let (symbol, segments) = l.split_once(':').ok_or(format!("Invalid glyph {}", l))?;
+                let symbol = symbol.trim().chars().next().ok_or("Missing symbol")?;
+                Ok((symbol, to_mask(segments.trim())))
+            })
+            .collect::<Result<_, String>>()?;
+        let all = glyphs.iter().fold(0, |acc, (_, g)| acc | g);
+        Ok(Self {
+            segments: 32 - all.leading_zeros() as usize,
+            glyphs,
+        })
     }
 }
 
-fn is_unique(length: usize) -> bool {
-    length == 2 || length == 4 || length == 3 || length == 7
-}
+impl Font {
+    fn with_len(&self, len: u32) -> impl Iterator<Item = u32> + '_ {
+        self.glyphs
+            .iter()
+            .map(|(_, g)| *g)
+            .filter(move |g| g.count_ones() == len)
+    }
 
-// Add the easy numbers to the number struct
-fn collect_known(numbers: &mut Numbers, patterns: &Vec<String>) {
-    for pattern in patterns {
-        match pattern.len() {
-            2 => numbers.one = pattern.chars().collect(),
-            3 => numbers.seven = pattern.chars().collect(),
-            4 => numbers.four = pattern.chars().collect(),
-            7 => numbers.eight = pattern.chars().collect(),
-            _ => {}
-        }
+    fn all(&self) -> u32 {
+        (1 << self.segments) - 1
     }
-}
 
-// Calculate if the pattern is either a 2, 3 or 5
-fn calculate_two_three_or_five(numbers: &mut Numbers, pattern: String) {
-    // 3 is the only one that contains all the same as 1
-    if numbers.one.iter().all(|&c| pattern.contains(c)) {
-        numbers.three = pattern.chars().collect();
-    } else {
-        // If the number of different chars between the pattern
-        // and the characters in four is 3, then we have found
-        // the pattern for 2. Else it is the pattern for 3.
-        let pat: HashSet<char> = pattern.chars().collect();
-        if pat.difference(&numbers.four).collect::<Vec<_>>().len() == 3 {
-            numbers.two = pattern.chars().collect();
-        } else {
-            numbers.five = pattern.chars().collect();
-        }
+    fn symbol(&self, segments: u32) -> Option<char> {
+        self.glyphs
+            .iter()
+            .find(|(_, g)| *g == segments)
+            .map(|(c, _)| *c)
     }
-}
 
-// Calculate the numbers 0, 6 or 9
-fn calculate_zero_six_or_nine(numbers: &mut Numbers, pattern: String) {
-    // 6 is the only one that doesn't contain all the same
-    // characters that are in 1
-    if !numbers.one.iter().all(|&c| pattern.contains(c)) {
-        numbers.six = pattern.chars().collect();
-    } else {
-        // Zero does not contain all the characters that 4 have
-        if !numbers.four.iter().all(|&c| pattern.contains(c)) {
-            numbers.zero = pattern.chars().collect();
-        } else {
-            numbers.nine = pattern.chars().collect();
+    fn is_unique(&self, len: u32) -> bool {
+        self.with_len(len).count() == 1
+    }
+
+    // Narrow the segments each wire can drive: a wire lit in a pattern must
+    // drive a segment of one of the glyphs that pattern could be
+    fn domains(&self, patterns: &[u32]) -> Vec<u32> {
+        let mut domains = vec![self.all(); self.segments];
+        for p in patterns {
+            let (lit, unlit) = self
+                .with_len(p.count_ones())
+                .fold((0, 0), |(lit, unlit), g| {
+                    (lit | g, unlit | (self.all() & !g))
+                });
+            for (wire, domain) in domains.iter_mut().enumerate() {
+                *domain &= if p & (1 << wire) != 0 { lit } else { unlit };
+            }
         }
+        domains
     }
-}
 
-// Iterate over the output and calculate what number the output is
-fn calculate_output(numbers: &Numbers, output: &Vec<String>) -> String {
-    let mut str = "".to_string();
-    for out in output {
-        let num: HashSet<char> = out.chars().collect();
-        for i in 0..10 {
-            if numbers[i].eq(&num) {
-                str.push_str(&i.to_string());
+    fn consistent(&self, patterns: &[u32], mapping: &[usize]) -> bool {
+        patterns.iter().all(|p| {
+            let (on, off) = mapping
+                .iter()
+                .enumerate()
+                .fold((0, 0), |(on, off), (wire, seg)| {
+                    if p & (1 << wire) != 0 {
+                        (on | 1 << seg, off)
+                    } else {
+                        (on, off | 1 << seg)
+                    }
+                });
+            self.with_len(p.count_ones())
+                .any(|g| g & on == on && g & off == 0)
+        })
+    }
+
+    fn search(
+        &self,
+        patterns: &[u32],
+        domains: &[u32],
+        mapping: &mut Vec<usize>,
+        used: u32,
+        solutions: &mut Vec<Vec<usize>>,
+    ) {
+        if solutions.len() > 1 {
+            return;
+        }
+        if mapping.len() == self.segments {
+            solutions.push(mapping.clone());
+            return;
+        }
+        let wire = mapping.len();
+        for seg in 0..self.segments {
+            if domains[wire] & !used & (1 << seg) == 0 {
+                continue;
+            }
+            mapping.push(seg);
+            if self.consistent(patterns, mapping) {
+                self.search(patterns, domains, mapping, used | 1 << seg, solutions);
             }
+            mapping.pop();
         }
     }
-    str
+
+    fn solve(&self, patterns: &[u32]) -> Decoding {
+        let mut solutions = Vec::new();
+        let domains = self.domains(patterns);
+        self.search(patterns, &domains, &mut Vec::new(), 0, &mut solutions);
+        match solutions.len() {
+            0 => Decoding::Inconsistent,
+            1 => Decoding::Unique(solutions.pop().unwrap()),
+            _ => Decoding::Ambiguous,
+        }
+    }
+
+    fn decode(&self, mapping: &[usize], wires: u32) -> Option<char> {
+        let segments = mapping
+            .iter()
+            .enumerate()
+            .filter(|(wire, _)| wires & (1 << wire) != 0)
+            .fold(0, |acc, (_, seg)| acc | 1 << seg);
+        self.symbol(segments)
+    }
 }
 
 fn parse(input: &[String]) -> Vec<Entry> {
@@ -147,51 +171,66 @@ fn parse(input: &[String]) -> Vec<Entry> {
         .iter()
         .map(|x| {
             let (pattern, output) = x.split_once('|').unwrap();
-            return Entry {
-                pattern: pattern.split_whitespace().map(|p| p.to_string()).collect(),
-                output: output.split_whitespace().map(|o| o.to_string()).collect(),
-            };
+            Entry {
+                pattern: pattern.split_whitespace().map(to_mask).collect(),
+                output: output.split_whitespace().map(to_mask).collect(),
+            }
         })
         .collect()
 }
 
-fn task_one(input: &[String]) -> usize {
-    let vec = parse(input);
-    let mut unique = 0;
-    for entry in &vec {
-        for output in &entry.output {
-            if is_unique(output.len()) {
-                unique += 1;
-            }
-        }
+fn get_font() -> Font {
+    match std::env::var("FONT") {
+        Ok(path) => std::fs::read_to_string(path).unwrap().parse().unwrap(),
+        Err(_) => SEVEN_SEGMENT.parse().unwrap(),
     }
-    unique
 }
 
-fn task_two(input: &[String]) -> usize {
-    let vec = parse(input);
-
-    let mut res: Vec<usize> = Vec::new();
-    let mut numbers = Numbers::new();
-    for entry in vec {
-        collect_known(&mut numbers, &entry.pattern);
-        for pattern in &entry.pattern {
-            if pattern.len() == 6 {
-                calculate_zero_six_or_nine(&mut numbers, pattern.to_string());
-            } else if pattern.len() == 5 {
-                calculate_two_three_or_five(&mut numbers, pattern.to_string());
-            }
-        }
-        let output = calculate_output(&numbers, &entry.output);
-        res.push(output.parse::<usize>().unwrap());
-    }
-    res.iter().sum()
+fn task_one(input: &[String]) -> Answer {
+    let font = get_font();
+    parse(input)
+        .iter()
+        .flat_map(|entry| &entry.output)
+        .filter(|output| font.is_unique(output.count_ones()))
+        .count()
+        .into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    let font = get_font();
+    parse(input)
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            let observed: Vec<_> = entry.pattern.iter().chain(&entry.output).copied().collect();
+            let mapping = match font.solve(&observed) {
+                Decoding::Unique(mapping) => mapping,
+                decoding => panic!("Entry {} is {:?}", idx + 1, decoding),
+            };
+            entry
+                .output
+                .iter()
+                .map(|wires| font.decode(&mapping, *wires).unwrap())
+                .collect::<String>()
+                .parse::<usize>()
+                .unwrap()
+        })
+        .sum::<usize>()
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -205,35 +244,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
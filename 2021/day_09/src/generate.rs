@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const NEIGHBOURS: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Breadth-first distance from the nearest low point over the cells that
+// aren't walls, along with which low point that is
+fn flood(walls: &[Vec<bool>], lows: &[(usize, usize)]) -> Vec<Vec<Option<(usize, usize)>>> {
+    let (h, w) = (walls.len(), walls[0].len());
+    let mut seen = vec![vec![None; w]; h];
+    let mut queue = VecDeque::new();
+    for (basin, (x, y)) in lows.iter().enumerate() {
+        seen[*y][*x] = Some((basin, 0));
+        queue.push_back((*x, *y));
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let (basin, distance) = seen[y][x].unwrap();
+        for (dx, dy) in NEIGHBOURS {
+            let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+            if nx < 0 || ny < 0 || nx >= w as i64 || ny >= h as i64 {
+                continue;
+            }
+            let (nx, ny) = (nx as usize, ny as usize);
+            if !walls[ny][nx] && seen[ny][nx].is_none() {
+                seen[ny][nx] = Some((basin, distance + 1));
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    seen
+}
+
+/// A `size` by `size` heightmap. Basins grow from random low points and are
+/// walled off from each other with 9s, so every basin has a single low point
+/// like in the puzzle
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let side = size.max(2);
+    let mut lows: Vec<(usize, usize)> = Vec::new();
+    for _ in 0..(side * side / 20).max(1) {
+        let (x, y) = (rng.below(side), rng.below(side));
+        if lows
+            .iter()
+            .all(|(lx, ly)| lx.abs_diff(x) + ly.abs_diff(y) > 1)
+        {
+            lows.push((x, y));
+        }
+    }
+
+    // Cells bordering another basin become walls, and so do cells too far
+    // from their low point to stay below 9. Low points are never walled, so
+    // they are kept apart from each other
+    let mut walls: Vec<Vec<bool>> = (0..side)
+        .map(|_| (0..side).map(|_| rng.chance(0.1)).collect())
+        .collect();
+    for (x, y) in &lows {
+        walls[*y][*x] = false;
+    }
+    let first = flood(&walls, &lows);
+    for y in 0..side {
+        for x in 0..side {
+            let Some((basin, _)) = first[y][x] else {
+                walls[y][x] = true;
+                continue;
+            };
+            let borders = NEIGHBOURS.iter().any(|(dx, dy)| {
+                let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+                if !(0..side as i64).contains(&nx) || !(0..side as i64).contains(&ny) {
+                    return false;
+                }
+                let (nx, ny) = (nx as usize, ny as usize);
+                first[ny][nx]
+                    .is_some_and(|(b, _)| b < basin || (b != basin && lows.contains(&(nx, ny))))
+            });
+            walls[y][x] |= borders && !lows.contains(&(x, y));
+        }
+    }
+
+    let heights = flood(&walls, &lows);
+    (0..side)
+        .map(|y| {
+            (0..side)
+                .map(|x| match heights[y][x] {
+                    Some((_, distance)) if distance < 9 && !walls[y][x] => {
+                        char::from_digit(distance as u32, 10).unwrap()
+                    }
+                    _ => '9',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Low points and basins are found directly from the definitions: a basin is
+// every cell connected to the low point without crossing a 9
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let map: Vec<Vec<u32>> = input
+        .iter()
+        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
+        .collect();
+    let (h, w) = (map.len() as i64, map[0].len() as i64);
+    let neighbours = |x: i64, y: i64| {
+        NEIGHBOURS
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |(nx, ny)| (0..w).contains(nx) && (0..h).contains(ny))
+    };
+
+    let mut risk = 0;
+    let mut basins = Vec::new();
+    for y in 0..h {
+        for x in 0..w {
+            let height = map[y as usize][x as usize];
+            if !neighbours(x, y).all(|(nx, ny)| map[ny as usize][nx as usize] > height) {
+                continue;
+            }
+            risk += height as usize + 1;
+
+            let mut basin = vec![(x, y)];
+            let mut i = 0;
+            while i < basin.len() {
+                let (bx, by) = basin[i];
+                for (nx, ny) in neighbours(bx, by) {
+                    if map[ny as usize][nx as usize] != 9 && !basin.contains(&(nx, ny)) {
+                        basin.push((nx, ny));
+                    }
+                }
+                i += 1;
+            }
+            basins.push(basin.len());
+        }
+    }
+    basins.sort_unstable_by(|a, b| b.cmp(a));
+    (risk.into(), basins.iter().take(3).product::<usize>().into())
+}
@@ -0,0 +1,70 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const PAIRS: [(char, char); 4] = [('(', ')'), ('[', ']'), ('{', '}'), ('<', '>')];
+
+/// `size` lines of navigation subsystem, each either corrupted or incomplete.
+/// There is always an odd number of incomplete lines so the middle score is
+/// well defined
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(1);
+    let incomplete = 2 * rng.below(size.div_ceil(2)) + 1;
+    let mut lines: Vec<String> = (0..size)
+        .map(|i| {
+            let mut line = String::new();
+            let mut stack = Vec::new();
+            let length = rng.below(30) + 2;
+            while line.len() < length || stack.is_empty() {
+                if stack.is_empty() || rng.chance(0.55) {
+                    let (open, close) = *rng.pick(&PAIRS);
+                    line.push(open);
+                    stack.push(close);
+                } else {
+                    line.push(stack.pop().unwrap());
+                }
+            }
+            if i >= incomplete {
+                // Close the wrong chunk
+                let expected = *stack.last().unwrap();
+                let wrong = PAIRS
+                    .iter()
+                    .map(|p| p.1)
+                    .filter(|c| *c != expected)
+                    .collect::<Vec<_>>();
+                line.push(*rng.pick(&wrong));
+                line.extend((0..rng.below(5)).map(|_| rng.pick(&PAIRS).0));
+            }
+            line
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines
+}
+
+// Removes matched pairs until none are left, which leaves either a wrong
+// closing character or the unclosed openings
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let mut syntax = 0;
+    let mut completions = Vec::new();
+    for line in input {
+        let mut rest = line.clone();
+        loop {
+            let shorter = PAIRS.iter().fold(rest.clone(), |s, (open, close)| {
+                s.replace(&format!("{}{}", open, close), "")
+            });
+            if shorter == rest {
+                break;
+            }
+            rest = shorter;
+        }
+
+        match rest.chars().find(|c| PAIRS.iter().any(|p| p.1 == *c)) {
+            Some(c) => syntax += [3, 57, 1197, 25137][PAIRS.iter().position(|p| p.1 == c).unwrap()],
+            None => completions.push(rest.chars().rev().fold(0u64, |score, c| {
+                score * 5 + 1 + PAIRS.iter().position(|p| p.0 == c).unwrap() as u64
+            })),
+        }
+    }
+    completions.sort_unstable();
+    (syntax.into(), completions[completions.len() / 2].into())
+}
@@ -0,0 +1,84 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+// Random grids don't always synchronise, so this bounds how long to look
+const SYNCHRONISE_WITHIN: usize = 5000;
+
+/// A `size` by `size` grid of octopus energy levels which all flash together
+/// at some point
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let side = size.max(1);
+    loop {
+        let grid: Vec<Vec<u32>> = (0..side)
+            .map(|_| (0..side).map(|_| rng.below(10) as u32).collect())
+            .collect();
+        let mut octopuses = grid.clone();
+        if (0..SYNCHRONISE_WITHIN).any(|_| step(&mut octopuses) == side * side) {
+            return grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|e| char::from_digit(*e, 10).unwrap())
+                        .collect()
+                })
+                .collect();
+        }
+    }
+}
+
+// Rescans the whole grid for octopuses ready to flash until none are left
+fn step(grid: &mut [Vec<u32>]) -> usize {
+    let (h, w) = (grid.len() as i64, grid[0].len() as i64);
+    grid.iter_mut().flatten().for_each(|e| *e += 1);
+    let mut flashed = vec![vec![false; w as usize]; h as usize];
+    loop {
+        let mut any = false;
+        for y in 0..h {
+            for x in 0..w {
+                if grid[y as usize][x as usize] <= 9 || flashed[y as usize][x as usize] {
+                    continue;
+                }
+                flashed[y as usize][x as usize] = true;
+                any = true;
+                for ny in y - 1..=y + 1 {
+                    for nx in x - 1..=x + 1 {
+                        if (0..h).contains(&ny) && (0..w).contains(&nx) {
+                            grid[ny as usize][nx as usize] += 1;
+                        }
+                    }
+                }
+            }
+        }
+        if !any {
+            break;
+        }
+    }
+    grid.iter_mut()
+        .flatten()
+        .filter(|e| **e > 9)
+        .for_each(|e| *e = 0);
+    flashed.iter().flatten().filter(|f| **f).count()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let mut grid: Vec<Vec<u32>> = input
+        .iter()
+        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect())
+        .collect();
+    let size = grid.len() * grid[0].len();
+
+    let mut flashes = 0;
+    let mut steps = 0;
+    let mut synchronised = None;
+    while steps < 100 || synchronised.is_none() {
+        steps += 1;
+        let flashed = step(&mut grid);
+        if steps <= 100 {
+            flashes += flashed;
+        }
+        if flashed == size && synchronised.is_none() {
+            synchronised = Some(steps);
+        }
+    }
+    (flashes.into(), synchronised.unwrap().into())
+}
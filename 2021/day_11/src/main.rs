@@ -1,99 +1,125 @@
-#[derive(Clone)]
-struct Octopus {
-    energy: u32,
-    visited: bool,
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+use matrix::Matrix;
+
+mod generate;
+#[allow(dead_code)]
+mod matrix;
+
+const NEIGHBOURS: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+struct Cavern {
+    energy: Matrix<u32>,
+    threshold: u32,
+    wrap: bool,
 }
 
-fn update(vec: &mut Vec<Vec<Octopus>>, i: i32, j: i32) {
-    if i < 0 || j < 0 || i >= vec.len() as i32 || j >= vec[0].len() as i32 {
-        return;
-    }
-    let i = i as usize;
-    let j = j as usize;
-    vec[i][j].energy += 1;
-    if vec[i][j].energy > 9 && !vec[i][j].visited {
-        vec[i][j].visited = true;
-        update_surroundings(vec, i, j);
+impl Cavern {
+    fn new(input: &[String]) -> Self {
+        let matrix = Matrix::from(input);
+        Self {
+            energy: Matrix::new(
+                matrix.iter().map(|(_, c)| (c - b'0') as u32).collect(),
+                (matrix.width(), matrix.height()),
+            ),
+            threshold: std::env::var("THRESHOLD").map_or(9, |t| t.parse().unwrap()),
+            wrap: std::env::var("WRAP").is_ok(),
+        }
     }
-}
 
-fn update_surroundings(vec: &mut Vec<Vec<Octopus>>, i: usize, j: usize) {
-    let i = i as i32;
-    let j = j as i32;
-    update(vec, i - 1, j + 1); // upper right corner
-    update(vec, i, j + 1); // right
-    update(vec, i + 1, j + 1); // lower right corner
-    update(vec, i + 1, j - 1); // lower left corner
-    update(vec, i, j - 1); // left
-    update(vec, i - 1, j - 1); // upper left corner
-    update(vec, i - 1, j); // up
-    update(vec, i + 1, j); // down
-}
+    fn size(&self) -> usize {
+        self.energy.width() * self.energy.height()
+    }
 
-fn parse(input: &[String]) -> Vec<Vec<Octopus>> {
-    input
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|c| Octopus {
-                    energy: c.to_digit(10).unwrap(),
-                    visited: false,
-                })
-                .collect()
+    fn neighbours(&self, (x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (w, h) = (self.energy.width() as i32, self.energy.height() as i32);
+        NEIGHBOURS.iter().filter_map(move |(dx, dy)| {
+            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+            if self.wrap {
+                Some((nx.rem_euclid(w) as usize, ny.rem_euclid(h) as usize))
+            } else {
+                self.energy
+                    .in_grid((nx, ny))
+                    .then_some((nx as usize, ny as usize))
+            }
         })
-        .collect()
-}
+    }
 
-fn task_one(input: &[String]) -> usize {
-    let mut vec = parse(input);
-    let mut flashes = 0;
-    for _ in 0..100 {
-        for i in 0..vec.len() {
-            for j in 0..vec[0].len() {
-                update(&mut vec, i as i32, j as i32);
-            }
-        }
-        for i in 0..vec.len() {
-            for j in 0..vec[0].len() {
-                if vec[i][j].energy > 9 {
-                    vec[i][j].energy = 0;
-                    vec[i][j].visited = false;
-                    flashes += 1;
+    // Returns the number of flashes. Flashing octopuses go on a worklist
+    // instead of recursing, and every octopus flashes at most once per step
+    fn step(&mut self) -> usize {
+        let (w, h) = (self.energy.width(), self.energy.height());
+        let mut flashed = Matrix::new(vec![false; self.size()], (w, h));
+        let mut worklist = Vec::new();
+
+        for y in 0..h {
+            for x in 0..w {
+                self.energy[(x, y)] += 1;
+                if self.energy[(x, y)] > self.threshold {
+                    flashed[(x, y)] = true;
+                    worklist.push((x, y));
                 }
             }
         }
-    }
-    flashes as usize
-}
 
-fn task_two(input: &[String]) -> usize {
-    let mut vec = parse(input);
-    for step in 1.. {
-        for i in 0..vec.len() {
-            for j in 0..vec[0].len() {
-                update(&mut vec, i as i32, j as i32);
+        let mut flashes = 0;
+        while let Some(pos) = worklist.pop() {
+            flashes += 1;
+            let neighbours: Vec<_> = self.neighbours(pos).collect();
+            for next in neighbours {
+                self.energy[next] += 1;
+                if self.energy[next] > self.threshold && !flashed[next] {
+                    flashed[next] = true;
+                    worklist.push(next);
+                }
             }
         }
-        // If visited all
-        if vec.iter().all(|x| x.iter().all(|c| c.visited)) {
-            return step;
-        }
-        for i in 0..vec.len() {
-            for j in 0..vec[0].len() {
-                if vec[i][j].energy > 9 {
-                    vec[i][j].energy = 0;
-                    vec[i][j].visited = false;
+
+        for y in 0..h {
+            for x in 0..w {
+                if flashed[(x, y)] {
+                    self.energy[(x, y)] = 0;
                 }
             }
         }
+        flashes
     }
-    unreachable!();
+}
+
+fn task_one(input: &[String]) -> Answer {
+    let mut cavern = Cavern::new(input);
+    (0..100).map(|_| cavern.step()).sum::<usize>().into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    let mut cavern = Cavern::new(input);
+    (1..)
+        .find(|_| cavern.step() == cavern.size())
+        .unwrap()
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -107,35 +133,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A cave system with `size` caves besides the start and end. Big caves are
+/// never connected to each other, so there are finitely many paths
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut caves = vec!["start".to_string(), "end".to_string()];
+    for i in 0..size {
+        let name: String = (0..2)
+            .map(|j| (b'a' + ((i * 7 + j * 3 + rng.below(26)) % 26) as u8) as char)
+            .collect();
+        let name = if rng.chance(0.3) {
+            name.to_uppercase()
+        } else {
+            name
+        };
+        if !caves.contains(&name) {
+            caves.push(name);
+        }
+    }
+    let big = |cave: &str| cave.chars().all(|c| c.is_ascii_uppercase());
+
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    for _ in 0..caves.len() * 3 / 2 {
+        let (a, b) = (rng.below(caves.len()), rng.below(caves.len()));
+        let (a, b) = (a.min(b), a.max(b));
+        if a != b && !(big(&caves[a]) && big(&caves[b])) && !edges.contains(&(a, b)) {
+            edges.push((a, b));
+        }
+    }
+    edges
+        .iter()
+        .map(|(a, b)| {
+            if rng.chance(0.5) {
+                format!("{}-{}", caves[*a], caves[*b])
+            } else {
+                format!("{}-{}", caves[*b], caves[*a])
+            }
+        })
+        .collect()
+}
+
+// Walks every path, carrying the caves visited so far
+fn walk<'a>(
+    graph: &HashMap<&'a str, Vec<&'a str>>,
+    path: &mut Vec<&'a str>,
+    revisit: bool,
+) -> usize {
+    let cave = *path.last().unwrap();
+    if cave == "end" {
+        return 1;
+    }
+    let mut count = 0;
+    for next in graph.get(cave).into_iter().flatten() {
+        let small = next.chars().all(|c| c.is_ascii_lowercase());
+        let seen = small && path.contains(next);
+        if *next == "start" || (seen && !revisit) {
+            continue;
+        }
+        path.push(next);
+        count += walk(graph, path, revisit && !seen);
+        path.pop();
+    }
+    count
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let mut graph: HashMap<&str, Vec<&str>> = HashMap::new();
+    for line in input {
+        let (a, b) = line.split_once('-').unwrap();
+        graph.entry(a).or_default().push(b);
+        graph.entry(b).or_default().push(a);
+    }
+    (
+        walk(&graph, &mut vec!["start"], false).into(),
+        walk(&graph, &mut vec!["start"], true).into(),
+    )
+}
@@ -1,118 +1,200 @@
 use std::collections::HashMap;
 
-trait IsLowercase {
-    fn is_lowercase(&self) -> bool;
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+#[derive(Debug)]
+struct Graph {
+    names: Vec<String>,
+    small: Vec<bool>,
+    neighbors: Vec<Vec<usize>>,
+    start: usize,
+    end: usize,
 }
 
-impl IsLowercase for String {
-    fn is_lowercase(&self) -> bool {
-        &self.to_lowercase() == self
-    }
+#[derive(Debug, Clone, Copy)]
+enum Policy {
+    SmallOnce,
+    OneSmallTwice,
+    AtMost(u8),
 }
 
-fn walk_1(
-    map: &HashMap<String, Vec<String>>,
-    city: String,
-    visited: &mut HashMap<String, bool>,
-    paths: &mut usize,
-) {
-    if city.to_lowercase() == "end" {
-        *paths += 1;
-        return;
-    }
-    // if the city is lowercase and we have visited it already
-    if city.is_lowercase() && *visited.get(&city).unwrap() {
-        return;
+// Which caves have been entered. A bit per cave does when only small caves
+// are counted and at most once, with `twice` for the one allowed second visit.
+// `Policy::AtMost` and graphs too big for the mask count every cave instead
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum Seen {
+    Mask(u128),
+    Counts(Vec<u8>),
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+struct Visits {
+    seen: Seen,
+    twice: bool,
+}
+
+impl Seen {
+    fn count(&self, cave: usize) -> u8 {
+        match self {
+            Seen::Mask(mask) => (mask >> cave & 1) as u8,
+            Seen::Counts(counts) => counts[cave],
+        }
     }
-    visited.insert(city.to_string(), true);
 
-    let neighbors = map.get(&city).unwrap();
-    for n in neighbors {
-        walk_1(map, n.to_string(), visited, paths);
+    fn enter(&mut self, cave: usize) {
+        match self {
+            Seen::Mask(mask) => *mask |= 1 << cave,
+            Seen::Counts(counts) => counts[cave] += 1,
+        }
     }
-    visited.insert(city.to_string(), false);
 }
 
-fn walk_2(
-    map: &HashMap<String, Vec<String>>,
-    city: String,
-    visited: &mut HashMap<String, usize>,
-    paths: &mut usize,
-) {
-    if city.to_lowercase() == "end" {
-        *paths += 1;
-        return;
+impl Graph {
+    fn intern(&mut self, name: &str) -> usize {
+        if let Some(id) = self.names.iter().position(|n| n == name) {
+            return id;
+        }
+        let id = self.names.len();
+        self.names.push(name.to_string());
+        self.neighbors.push(Vec::new());
+        self.small
+            .push(name.chars().all(|c| c.is_ascii_lowercase()));
+        id
     }
-    // if the city is lowercase and another city has been visited twice
-    // and we already have visited this city before
-    if city.is_lowercase()
-        && *visited.get(&city.to_string()).unwrap() >= 1
-        && visited
-            .iter()
-            .filter(|x| x.0.is_lowercase() && *x.1 == 2)
-            .count()
-            > 0
-    {
-        return;
+
+    fn unvisited(&self, policy: Policy) -> Visits {
+        let seen = match policy {
+            Policy::SmallOnce | Policy::OneSmallTwice if self.names.len() <= 128 => Seen::Mask(0),
+            _ => Seen::Counts(vec![0; self.names.len()]),
+        };
+        Visits { seen, twice: false }
     }
-    *visited.get_mut(&city.to_string()).unwrap() += 1;
 
-    let neighbors = map.get(&city).unwrap();
-    for n in neighbors {
-        walk_2(map, n.to_string(), visited, paths);
+    fn visit(&self, visits: &Visits, cave: usize, policy: Policy) -> Option<Visits> {
+        let seen = visits.seen.count(cave);
+        let entered = |twice| {
+            let mut visits = Visits {
+                twice,
+                ..visits.clone()
+            };
+            visits.seen.enter(cave);
+            visits
+        };
+        match policy {
+            _ if cave == self.start => None,
+            Policy::SmallOnce | Policy::OneSmallTwice if !self.small[cave] => Some(visits.clone()),
+            Policy::SmallOnce if seen > 0 => None,
+            Policy::OneSmallTwice if seen > 0 => (!visits.twice).then(|| entered(true)),
+            Policy::SmallOnce | Policy::OneSmallTwice => Some(entered(visits.twice)),
+            Policy::AtMost(k) => (seen < k).then(|| entered(visits.twice)),
+        }
     }
-    *visited.get_mut(&city.to_string()).unwrap() -= 1;
-}
 
-fn parse(input: &[String]) -> HashMap<String, Vec<String>> {
-    let mut m: HashMap<String, Vec<String>> = HashMap::new();
-    for v in input {
-        let (a, b) = v.split_once('-').unwrap();
-        if b != "start" {
-            m.entry(a.to_string()).or_default().push(b.to_string());
+    fn count_paths(
+        &self,
+        cave: usize,
+        visits: Visits,
+        policy: Policy,
+        memo: &mut [HashMap<Visits, usize>],
+    ) -> usize {
+        if cave == self.end {
+            return 1;
         }
-        if a != "start" {
-            m.entry(b.to_string()).or_default().push(a.to_string());
+        if let Some(paths) = memo[cave].get(&visits) {
+            return *paths;
         }
+
+        let paths = self.neighbors[cave]
+            .iter()
+            .filter_map(|next| Some((*next, self.visit(&visits, *next, policy)?)))
+            .map(|(next, visits)| self.count_paths(next, visits, policy, memo))
+            .sum();
+        memo[cave].insert(visits, paths);
+        paths
     }
-    m.remove("end");
-    m
-}
 
-fn task_one(input: &[String]) -> usize {
-    let map = parse(input);
-    let mut visited: HashMap<String, bool> = HashMap::new();
-    for k in map.keys() {
-        visited.insert(k.to_string(), false);
+    fn for_each_path<F>(&self, path: &mut Vec<usize>, visits: &Visits, policy: Policy, f: &mut F)
+    where
+        F: FnMut(&[usize]),
+    {
+        let cave = *path.last().unwrap();
+        if cave == self.end {
+            f(path);
+            return;
+        }
+        for next in &self.neighbors[cave] {
+            if let Some(visits) = self.visit(visits, *next, policy) {
+                path.push(*next);
+                self.for_each_path(path, &visits, policy, f);
+                path.pop();
+            }
+        }
     }
 
-    let mut paths = 0;
-    for city in map.get("start").unwrap() {
-        walk_1(&map, city.to_string(), &mut visited, &mut paths);
-        visited.iter_mut().for_each(|x| *x.1 = false);
+    fn paths(&self, policy: Policy) -> usize {
+        if std::env::var("PATHS").is_ok() {
+            self.for_each_path(
+                &mut vec![self.start],
+                &self.unvisited(policy),
+                policy,
+                &mut |path| {
+                    let names: Vec<_> =
+                        path.iter().map(|cave| self.names[*cave].as_str()).collect();
+                    println!("{}", names.join(","));
+                },
+            );
+        }
+        let mut memo = vec![HashMap::new(); self.names.len()];
+        self.count_paths(self.start, self.unvisited(policy), policy, &mut memo)
     }
-    paths
 }
 
-fn task_two(input: &[String]) -> usize {
-    let map = parse(input);
-    let mut visited: HashMap<String, usize> = HashMap::new();
-    for k in map.keys() {
-        visited.insert(k.to_string(), 0);
-    }
+fn parse(input: &[String]) -> Graph {
+    let mut graph = Graph {
+        names: Vec::new(),
+        small: Vec::new(),
+        neighbors: Vec::new(),
+        start: 0,
+        end: 0,
+    };
 
-    let mut paths = 0;
-    for city in map.get("start").unwrap() {
-        walk_2(&map, city.to_string(), &mut visited, &mut paths);
-        visited.iter_mut().for_each(|x| *x.1 = 0);
+    for v in input {
+        let (a, b) = v.split_once('-').unwrap();
+        let (a, b) = (graph.intern(a), graph.intern(b));
+        graph.neighbors[a].push(b);
+        graph.neighbors[b].push(a);
     }
-    paths
+
+    graph.start = graph.intern("start");
+    graph.end = graph.intern("end");
+    graph
+}
+
+fn task_one(input: &[String]) -> Answer {
+    parse(input)
+        .paths(get_policy().unwrap_or(Policy::SmallOnce))
+        .into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    parse(input).paths(Policy::OneSmallTwice).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -126,35 +208,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -164,3 +242,9 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn get_policy() -> Option<Policy> {
+    std::env::var("MAXVISITS")
+        .ok()
+        .map(|k| Policy::AtMost(k.parse().unwrap()))
+}
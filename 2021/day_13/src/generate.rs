@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A transparent sheet with `size` folds. The dots start out on a small
+/// sheet which is unfolded once per fold, mirroring dots at random, so every
+/// fold line is exactly in the middle of the sheet like in the puzzle
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let (mut width, mut height) = (rng.range(3..12) as usize, rng.range(3..8) as usize);
+    let mut dots: HashSet<(usize, usize)> = (0..width * height / 3)
+        .map(|_| (rng.below(width), rng.below(height)))
+        .collect();
+
+    let mut folds = Vec::new();
+    for _ in 0..size.max(1) {
+        let along_x = rng.chance(0.5);
+        let line = if along_x { width } else { height };
+        let mirror = |v: usize| 2 * line - v;
+        dots = dots
+            .iter()
+            .flat_map(|(x, y)| {
+                let mirrored = if along_x {
+                    (mirror(*x), *y)
+                } else {
+                    (*x, mirror(*y))
+                };
+                match rng.below(3) {
+                    0 => vec![(*x, *y)],
+                    1 => vec![mirrored],
+                    _ => vec![(*x, *y), mirrored],
+                }
+            })
+            .collect();
+        if along_x {
+            width = 2 * width + 1;
+        } else {
+            height = 2 * height + 1;
+        }
+        folds.push(format!(
+            "fold along {}={}",
+            if along_x { 'x' } else { 'y' },
+            line
+        ));
+    }
+
+    // The sheet is only as large as the dots on it. The first row and column
+    // are never a fold line, which dots can't be on
+    dots.insert((width - 1, 0));
+    dots.insert((0, height - 1));
+    let mut dots: Vec<_> = dots.into_iter().collect();
+    rng.shuffle(&mut dots);
+
+    let mut lines: Vec<String> = dots.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+    lines.push(String::new());
+    lines.extend(folds.into_iter().rev());
+    lines
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let mut dots: HashSet<(usize, usize)> = input
+        .iter()
+        .filter_map(|l| l.split_once(','))
+        .map(|(x, y)| (x.parse().unwrap(), y.parse().unwrap()))
+        .collect();
+    let mut width = dots.iter().map(|d| d.0).max().unwrap() + 1;
+    let mut height = dots.iter().map(|d| d.1).max().unwrap() + 1;
+
+    let mut first = None;
+    for fold in input.iter().filter_map(|l| l.strip_prefix("fold along ")) {
+        let (axis, line) = fold.split_once('=').unwrap();
+        let line: usize = line.parse().unwrap();
+        dots = dots
+            .into_iter()
+            .map(|(x, y)| match axis {
+                "x" if x > line => (2 * line - x, y),
+                "y" if y > line => (x, 2 * line - y),
+                _ => (x, y),
+            })
+            .collect();
+        match axis {
+            "x" => width = line,
+            _ => height = line,
+        }
+        first.get_or_insert(dots.len());
+    }
+
+    let rows = (0..height).map(|y| {
+        (0..width)
+            .map(|x| if dots.contains(&(x, y)) { '#' } else { '.' })
+            .collect::<String>()
+    });
+    (first.unwrap().into(), Answer::glyphs(rows))
+}
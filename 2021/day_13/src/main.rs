@@ -1,3 +1,8 @@
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Clone, Debug)]
 struct Coordinate {
     x: usize,
@@ -42,12 +47,6 @@ struct Map {
     max_y: usize,
 }
 
-fn print_paper(paper: &Vec<Vec<char>>) {
-    for x in paper {
-        println!("{:?}", x);
-    }
-}
-
 fn fold_vertical(paper: &mut Vec<Vec<char>>, position: usize) {
     for i in 0..paper.len() {
         for j in position + 1..paper[0].len() {
@@ -106,7 +105,7 @@ fn parse(input: &[String]) -> Map {
     }
 }
 
-fn task_one(input: &[String]) -> usize {
+fn task_one(input: &[String]) -> Answer {
     let map = parse(input);
     let mut paper = vec![vec!['.'; map.max_x + 1]; map.max_y + 1];
 
@@ -122,10 +121,10 @@ fn task_one(input: &[String]) -> usize {
         _ => unreachable!(),
     }
 
-    paper.iter().flatten().filter(|&&x| x == '#').count()
+    paper.iter().flatten().filter(|&&x| x == '#').count().into()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     let map = parse(input);
     let mut paper = vec![vec!['.'; map.max_x + 1]; map.max_y + 1];
 
@@ -142,14 +141,21 @@ fn task_two(input: &[String]) -> usize {
         }
     }
 
-    print_paper(&paper);
-    0
+    Answer::glyphs(paper.iter().map(|row| row.iter().collect::<String>()))
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -163,35 +169,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A polymer template of `size` elements, with an insertion rule for every
+/// pair of elements
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let elements: Vec<char> = (b'A'..=b'Z')
+        .map(char::from)
+        .filter(|_| rng.chance(0.3))
+        .chain(['N', 'C'])
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut lines = vec![(0..size.max(2))
+        .map(|_| *rng.pick(&elements))
+        .collect::<String>()];
+    lines.push(String::new());
+    for a in &elements {
+        for b in &elements {
+            lines.push(format!("{}{} -> {}", a, b, rng.pick(&elements)));
+        }
+    }
+    lines
+}
+
+// Element counts added between `a` and `b` over `steps`, not counting either
+fn between(
+    rules: &HashMap<(char, char), char>,
+    (a, b): (char, char),
+    steps: u32,
+    memo: &mut HashMap<(char, char, u32), HashMap<char, u128>>,
+) -> HashMap<char, u128> {
+    if steps == 0 {
+        return HashMap::new();
+    }
+    if let Some(counts) = memo.get(&(a, b, steps)) {
+        return counts.clone();
+    }
+    let c = rules[&(a, b)];
+    let mut counts = between(rules, (a, c), steps - 1, memo);
+    for (element, count) in between(rules, (c, b), steps - 1, memo) {
+        *counts.entry(element).or_default() += count;
+    }
+    *counts.entry(c).or_default() += 1;
+    memo.insert((a, b, steps), counts.clone());
+    counts
+}
+
+fn spread(counts: &HashMap<char, u128>) -> u128 {
+    counts.values().max().unwrap() - counts.values().min().unwrap()
+}
+
+// Ten steps are small enough to build the polymer itself, while forty steps
+// recurse on each pair of the template
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let rules: HashMap<(char, char), char> = input[2..]
+        .iter()
+        .map(|l| {
+            let (pair, insert) = l.split_once(" -> ").unwrap();
+            let mut pair = pair.chars();
+            (
+                (pair.next().unwrap(), pair.next().unwrap()),
+                insert.chars().next().unwrap(),
+            )
+        })
+        .collect();
+
+    let mut polymer: Vec<char> = input[0].chars().collect();
+    for _ in 0..10 {
+        let mut next = vec![polymer[0]];
+        for pair in polymer.windows(2) {
+            next.push(rules[&(pair[0], pair[1])]);
+            next.push(pair[1]);
+        }
+        polymer = next;
+    }
+    let mut short: HashMap<char, u128> = HashMap::new();
+    for element in polymer {
+        *short.entry(element).or_default() += 1;
+    }
+
+    let template: Vec<char> = input[0].chars().collect();
+    let mut long: HashMap<char, u128> = HashMap::new();
+    let mut memo = HashMap::new();
+    for element in &template {
+        *long.entry(*element).or_default() += 1;
+    }
+    for pair in template.windows(2) {
+        for (element, count) in between(&rules, (pair[0], pair[1]), 40, &mut memo) {
+            *long.entry(element).or_default() += count;
+        }
+    }
+    (spread(&short).into(), spread(&long).into())
+}
@@ -1,134 +1,235 @@
-use std::collections::HashMap;
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
 
 #[derive(Debug)]
-struct Map {
-    template: Vec<char>,
-    pairs: HashMap<String, char>,
+struct Polymer {
+    elements: Vec<char>,
+    template: Vec<usize>,
+    // Element inserted between each pair, indexed by `a * elements.len() + b`
+    rules: Vec<Option<usize>>,
 }
 
-fn get_starting_frequency(template: &[char]) -> HashMap<char, usize> {
-    let mut counter: HashMap<char, usize> = HashMap::new();
-    template.iter().for_each(|&x| {
-        let count = counter.entry(x).or_insert(0);
-        *count += 1;
-    });
-    counter
+#[derive(Debug, Clone)]
+struct Matrix {
+    size: usize,
+    data: Vec<u128>,
 }
 
-fn get_start_frequency(template: &[char]) -> HashMap<String, usize> {
-    let mut counter: HashMap<String, usize> = HashMap::new();
-    template.windows(2).for_each(|x| {
-        let string: String = x.iter().collect();
-        let count = counter.entry(string).or_insert(0);
-        *count += 1;
-    });
-    counter
+fn add(a: u128, b: u128, modulus: Option<u128>) -> u128 {
+    match modulus {
+        Some(m) => (a + b) % m,
+        None => a.checked_add(b).expect("Overflow, set MODULO"),
+    }
 }
 
-fn parse(input: &[String]) -> Map {
-    let mut pairs: HashMap<String, char> = HashMap::new();
-    input.iter().skip(2).for_each(|x| {
-        let (k, v) = x.split_once(" -> ").unwrap();
-        pairs.insert(k.to_string(), v.parse::<char>().unwrap());
-    });
-
-    let template: Vec<char> = input.first().unwrap().chars().collect();
-    Map { pairs, template }
+fn mul(a: u128, b: u128, modulus: Option<u128>) -> u128 {
+    match modulus {
+        Some(m) => (a * b) % m,
+        None => a.checked_mul(b).expect("Overflow, set MODULO"),
+    }
 }
 
-fn task_one(input: &[String]) -> usize {
-    let map = parse(input);
-    let mut template = map.template.clone();
-    let mut frequency = get_starting_frequency(&template);
-    for _ in 0..10 {
-        let mut next = template.clone();
-        template.windows(2).enumerate().for_each(|(i, x)| {
-            let chars: String = x.iter().collect();
-            let char = map.pairs.get(&chars).unwrap();
-
-            let count = frequency.entry(*char).or_insert(0);
-            *count += 1;
-            next.insert(2 * i + 1, *char);
-        });
-        template = next;
+impl Matrix {
+    fn identity(size: usize) -> Self {
+        let mut data = vec![0; size * size];
+        (0..size).for_each(|i| data[i * size + i] = 1);
+        Self { size, data }
     }
-    let max = frequency.iter().max_by(|a, b| a.1.cmp(b.1)).unwrap().1;
-    let min = frequency.iter().min_by(|a, b| a.1.cmp(b.1)).unwrap().1;
-    max - min
-}
 
-fn task_two(input: &[String]) -> usize {
-    let map = parse(input);
-    let template = map.template.clone();
-    let mut frequency = get_start_frequency(&template);
-    for _ in 0..40 {
-        let mut next = frequency.clone();
-        frequency.iter().for_each(|(k, v)| {
-            let char = *map.pairs.get(k).unwrap();
-            let (c1, c2) = k.split_at(1);
-            let pair1 = format!("{}{}", c1, char);
-            let pair2 = format!("{}{}", char, c2);
-
-            let existing = next.entry(k.to_string()).or_default();
-            if *existing == *v {
-                next.remove_entry(k);
-            } else {
-                *existing -= v;
+    fn mul(&self, other: &Matrix, modulus: Option<u128>) -> Matrix {
+        let n = self.size;
+        let mut data = vec![0; n * n];
+        for i in 0..n {
+            for k in 0..n {
+                let a = self.data[i * n + k];
+                if a == 0 {
+                    continue;
+                }
+                for j in 0..n {
+                    let b = other.data[k * n + j];
+                    if b != 0 {
+                        data[i * n + j] = add(data[i * n + j], mul(a, b, modulus), modulus);
+                    }
+                }
             }
+        }
+        Matrix { size: n, data }
+    }
 
-            let count1 = next.entry(pair1).or_insert(0);
-            *count1 += v;
+    fn pow(&self, mut exp: u64, modulus: Option<u128>) -> Matrix {
+        let mut base = self.clone();
+        let mut res = Matrix::identity(self.size);
+        while exp > 0 {
+            if exp & 1 == 1 {
+                res = res.mul(&base, modulus);
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.mul(&base, modulus);
+            }
+        }
+        res
+    }
 
-            let count2 = next.entry(pair2).or_insert(0);
-            *count2 += v;
-        });
-        frequency = next;
+    fn apply(&self, vector: &[u128], modulus: Option<u128>) -> Vec<u128> {
+        (0..self.size)
+            .map(|i| {
+                (0..self.size).fold(0, |acc, j| {
+                    add(
+                        acc,
+                        mul(self.data[i * self.size + j], vector[j], modulus),
+                        modulus,
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+impl Polymer {
+    fn pair(&self, a: usize, b: usize) -> usize {
+        a * self.elements.len() + b
+    }
+
+    fn num_pairs(&self) -> usize {
+        self.elements.len() * self.elements.len()
     }
 
-    let mut res_map: HashMap<char, usize> = HashMap::new();
-    for (k, v) in &frequency {
-        let (c1, c2) = k.split_at(1);
-        let c1 = c1.parse::<char>().unwrap();
-        let c2 = c2.parse::<char>().unwrap();
-        if c1 == c2 {
-            continue;
+    // Each pair turns into one or two pairs in the next step
+    fn targets(&self, pair: usize) -> Vec<usize> {
+        let n = self.elements.len();
+        let (a, b) = (pair / n, pair % n);
+        match self.rules[pair] {
+            Some(c) => vec![self.pair(a, c), self.pair(c, b)],
+            None => vec![pair],
         }
-        let res1 = res_map.entry(c1).or_insert(0);
-        *res1 += v;
+    }
 
-        let res2 = res_map.entry(c2).or_insert(0);
-        *res2 += v;
+    fn initial(&self) -> Vec<u128> {
+        let mut counts = vec![0; self.num_pairs()];
+        self.template
+            .windows(2)
+            .for_each(|w| counts[self.pair(w[0], w[1])] += 1);
+        counts
+    }
+
+    fn step(&self, counts: &[u128], modulus: Option<u128>) -> Vec<u128> {
+        let mut next = vec![0; counts.len()];
+        for (pair, count) in counts.iter().enumerate().filter(|(_, c)| **c > 0) {
+            for target in self.targets(pair) {
+                next[target] = add(next[target], *count, modulus);
+            }
+        }
+        next
+    }
+
+    fn matrix(&self) -> Matrix {
+        let size = self.num_pairs();
+        let mut data = vec![0; size * size];
+        for pair in 0..size {
+            for target in self.targets(pair) {
+                data[target * size + pair] += 1;
+            }
+        }
+        Matrix { size, data }
     }
 
-    let a = res_map.clone();
-    for (k, _) in a {
-        let res = res_map.entry(k).or_default();
-        let a = *res as f64;
-        let a = a / 2_f64;
-        let a = a.ceil();
-        *res = a as usize;
+    fn counts_after(&self, steps: u64, modulus: Option<u128>) -> Vec<u128> {
+        let counts = self.initial();
+        if steps <= self.num_pairs() as u64 {
+            (0..steps).fold(counts, |counts, _| self.step(&counts, modulus))
+        } else {
+            self.matrix().pow(steps, modulus).apply(&counts, modulus)
+        }
     }
 
-    for (k, v) in &frequency {
-        let (c1, c2) = k.split_at(1);
-        let c1 = c1.parse::<char>().unwrap();
-        let c2 = c2.parse::<char>().unwrap();
-        if c1 != c2 {
-            continue;
+    // Every element is the first of exactly one pair, except the last one in
+    // the template which never moves
+    fn histogram(&self, counts: &[u128], modulus: Option<u128>) -> Vec<(char, u128)> {
+        let n = self.elements.len();
+        let mut histogram = vec![0; n];
+        for (pair, count) in counts.iter().enumerate() {
+            histogram[pair / n] = add(histogram[pair / n], *count, modulus);
         }
-        let res1 = res_map.entry(c1).or_insert(0);
-        *res1 += v;
+        let last = *self.template.last().unwrap();
+        histogram[last] = add(histogram[last], 1, modulus);
+
+        let mut histogram: Vec<_> = self.elements.iter().copied().zip(histogram).collect();
+        histogram.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        histogram
+    }
+
+    fn difference(&self, steps: u64) -> u128 {
+        let histogram = self.histogram(&self.counts_after(steps, None), None);
+        let counts = histogram.iter().map(|(_, c)| *c).filter(|c| *c > 0);
+        counts.clone().max().unwrap() - counts.min().unwrap()
     }
+}
+
+fn parse(input: &[String]) -> Polymer {
+    let mut elements: Vec<char> = input[0].chars().collect();
+    input.iter().skip(2).for_each(|x| {
+        let (k, v) = x.split_once(" -> ").unwrap();
+        elements.extend(k.chars().chain(v.chars()));
+    });
+    elements.sort_unstable();
+    elements.dedup();
+
+    let index = |c: char| elements.iter().position(|e| *e == c).unwrap();
+    let n = elements.len();
+    let mut rules = vec![None; n * n];
+    input.iter().skip(2).for_each(|x| {
+        let (k, v) = x.split_once(" -> ").unwrap();
+        let mut k = k.chars().map(index);
+        let (a, b) = (k.next().unwrap(), k.next().unwrap());
+        rules[a * n + b] = Some(index(v.chars().next().unwrap()));
+    });
 
-    let max = res_map.iter().max_by(|a, b| a.1.cmp(b.1)).unwrap().1;
-    let min = res_map.iter().min_by(|a, b| a.1.cmp(b.1)).unwrap().1;
-    max - min
+    Polymer {
+        template: input[0].chars().map(index).collect(),
+        elements,
+        rules,
+    }
+}
+
+fn task_one(input: &[String]) -> Answer {
+    parse(input).difference(10).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    parse(input).difference(40).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
+
+    // Counts roughly double every step and overflow after about a hundred,
+    // so larger STEPS need a MODULO. It has to fit in a u64 so the product of
+    // two reduced counts still fits in a u128.
+    if let Ok(steps) = std::env::var("STEPS") {
+        let polymer = parse(&input);
+        let modulus = std::env::var("MODULO").ok().map(|m| {
+            m.parse::<std::num::NonZeroU64>()
+                .expect("MODULO must be between 1 and 2^64 - 1")
+                .get() as u128
+        });
+        let counts = polymer.counts_after(steps.parse().unwrap(), modulus);
+        for (element, count) in polymer.histogram(&counts, modulus) {
+            println!("{}: {}", element, count);
+        }
+    }
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -142,35 +243,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,62 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A `size` by `size` map of risk levels
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| char::from_digit(rng.range(1..10) as u32, 10).unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+// Relaxes every cell until nothing improves, instead of using a queue
+fn lowest_risk(map: &[Vec<u64>]) -> u64 {
+    let (h, w) = (map.len(), map[0].len());
+    let mut risk = vec![vec![u64::MAX; w]; h];
+    risk[0][0] = 0;
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for y in 0..h {
+            for x in 0..w {
+                let neighbours = [
+                    (x > 0).then(|| risk[y][x - 1]),
+                    (x + 1 < w).then(|| risk[y][x + 1]),
+                    (y > 0).then(|| risk[y - 1][x]),
+                    (y + 1 < h).then(|| risk[y + 1][x]),
+                ];
+                if let Some(best) = neighbours
+                    .iter()
+                    .flatten()
+                    .filter(|r| **r != u64::MAX)
+                    .min()
+                {
+                    if best + map[y][x] < risk[y][x] {
+                        risk[y][x] = best + map[y][x];
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+    risk[h - 1][w - 1]
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let map: Vec<Vec<u64>> = input
+        .iter()
+        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap() as u64).collect())
+        .collect();
+    let (h, w) = (map.len(), map[0].len());
+    let full: Vec<Vec<u64>> = (0..5 * h)
+        .map(|y| {
+            (0..5 * w)
+                .map(|x| (map[y % h][x % w] + (y / h + x / w) as u64 - 1) % 9 + 1)
+                .collect()
+        })
+        .collect();
+    (lowest_risk(&map).into(), lowest_risk(&full).into())
+}
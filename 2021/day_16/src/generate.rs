@@ -0,0 +1,147 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const LIMIT: i64 = 1 << 40;
+
+fn bits(value: u64, width: usize) -> String {
+    format!("{:0width$b}", value, width = width)
+}
+
+// Encodes a random packet with at most `budget` operators below it, returning
+// its bits and value
+fn packet(rng: &mut Rng, budget: &mut usize) -> (String, i64) {
+    let version = rng.below(8) as u64;
+    if *budget == 0 || rng.chance(0.4) {
+        let magnitude = rng.below(24);
+        let value = rng.range(0..1 << magnitude);
+        let groups = format!("{:b}", value).len().div_ceil(4);
+        let padded = bits(value as u64, groups * 4);
+        let mut encoded = bits(version, 3) + "100";
+        for (i, group) in padded.as_bytes().chunks(4).enumerate() {
+            encoded.push(if i + 1 < groups { '1' } else { '0' });
+            encoded.push_str(std::str::from_utf8(group).unwrap());
+        }
+        return (encoded, value);
+    }
+
+    *budget -= 1;
+    let type_id = *rng.pick(&[0, 1, 2, 3, 5, 6, 7]);
+    let count = if type_id >= 5 { 2 } else { rng.below(4) + 1 };
+    let children: Vec<(String, i64)> = (0..count).map(|_| packet(rng, budget)).collect();
+    let values = children.iter().map(|c| c.1);
+    // Every value stays below the limit, so a product that would pass it
+    // becomes a sum, and a sum that would becomes a minimum
+    let (type_id, value) = match type_id {
+        0 | 1 => {
+            let sum: i64 = values.clone().sum();
+            let product = values
+                .clone()
+                .try_fold(1i64, |p, v| p.checked_mul(v).filter(|p| *p < LIMIT));
+            match product {
+                Some(product) if type_id == 1 => (1, product),
+                _ if sum < LIMIT => (0, sum),
+                _ => (2, values.min().unwrap()),
+            }
+        }
+        2 => (2, values.min().unwrap()),
+        3 => (3, values.max().unwrap()),
+        5 => (5, (children[0].1 > children[1].1) as i64),
+        6 => (6, (children[0].1 < children[1].1) as i64),
+        _ => (7, (children[0].1 == children[1].1) as i64),
+    };
+
+    let body: String = children.iter().map(|c| c.0.as_str()).collect();
+    let header = bits(version, 3) + &bits(type_id, 3);
+    let encoded = if body.len() < 1 << 15 && rng.chance(0.5) {
+        header + "0" + &bits(body.len() as u64, 15) + &body
+    } else {
+        header + "1" + &bits(count as u64, 11) + &body
+    };
+    (encoded, value)
+}
+
+/// A transmission with at most `size` operator packets
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut budget = size;
+    let (mut encoded, _) = packet(rng, &mut budget);
+    while encoded.len() % 4 != 0 {
+        encoded.push('0');
+    }
+    let hex: String = encoded
+        .as_bytes()
+        .chunks(4)
+        .map(|nibble| {
+            let nibble = u32::from_str_radix(std::str::from_utf8(nibble).unwrap(), 2).unwrap();
+            char::from_digit(nibble, 16).unwrap().to_ascii_uppercase()
+        })
+        .collect();
+    vec![hex]
+}
+
+struct Reader {
+    bits: Vec<u8>,
+    pos: usize,
+}
+
+impl Reader {
+    fn read(&mut self, n: usize) -> u64 {
+        let value = self.bits[self.pos..self.pos + n]
+            .iter()
+            .fold(0, |acc, b| acc << 1 | *b as u64);
+        self.pos += n;
+        value
+    }
+
+    // Version sum and value of the next packet
+    fn packet(&mut self) -> (u64, i64) {
+        let version = self.read(3);
+        let type_id = self.read(3);
+        if type_id == 4 {
+            let mut value = 0;
+            loop {
+                let more = self.read(1);
+                value = value << 4 | self.read(4) as i64;
+                if more == 0 {
+                    return (version, value);
+                }
+            }
+        }
+
+        let mut children = Vec::new();
+        if self.read(1) == 0 {
+            let length = self.read(15) as usize;
+            let end = self.pos + length;
+            while self.pos < end {
+                children.push(self.packet());
+            }
+        } else {
+            for _ in 0..self.read(11) {
+                children.push(self.packet());
+            }
+        }
+        let versions = version + children.iter().map(|c| c.0).sum::<u64>();
+        let values: Vec<i64> = children.iter().map(|c| c.1).collect();
+        let value = match type_id {
+            0 => values.iter().sum(),
+            1 => values.iter().product(),
+            2 => *values.iter().min().unwrap(),
+            3 => *values.iter().max().unwrap(),
+            5 => (values[0] > values[1]) as i64,
+            6 => (values[0] < values[1]) as i64,
+            _ => (values[0] == values[1]) as i64,
+        };
+        (versions, value)
+    }
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let bits = input[0]
+        .chars()
+        .flat_map(|c| {
+            let nibble = c.to_digit(16).unwrap();
+            (0..4).rev().map(move |i| (nibble >> i & 1) as u8)
+        })
+        .collect();
+    let (versions, value) = Reader { bits, pos: 0 }.packet();
+    (versions.into(), value.into())
+}
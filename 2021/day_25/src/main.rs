@@ -1,18 +1,27 @@
-fn task_one(input: &[String]) -> usize
+use aoc::runner::{self, Task};
+use aoc::Answer;
+
+fn task_one(_input: &[String]) -> Answer
 {
-    unimplemented!()
+    Answer::Unsolved
 }
 
-fn task_two(input: &[String]) -> usize
+fn task_two(_input: &[String]) -> Answer
 {
-    unimplemented!()
+    Answer::Unsolved
 }
 
 fn main()
 {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir()
+    {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -22,40 +31,35 @@ where
     std::fs::read_to_string(path).unwrap().lines().map(String::from).collect()
 }
 
-enum Task
+fn time(task: Task, input: &[String])
 {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str()
+    let (usage, res) = match runner::part(input, task)
     {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+        Ok((res, usage)) =>
+        {
+            let (u, elapsed) = match fmt.as_str()
+            {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (format!("{}{u}, {}", elapsed, usage.memory()), res.to_string())
+        },
+        Err(status) => ("-".to_string(), status),
     };
 
     match task
     {
         Task::One =>
         {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         },
         Task::Two =>
         {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         },
     };
 }
@@ -0,0 +1,32 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// The food carried by `size` elves, with a blank line between elves
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for elf in 0..size.max(1) {
+        if elf > 0 {
+            lines.push(String::new());
+        }
+        lines.extend((0..rng.range(1..15)).map(|_| rng.range(1000..10000).to_string()));
+    }
+    lines
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let mut elves = vec![0];
+    for line in input {
+        match line.parse::<i64>() {
+            Ok(calories) => *elves.last_mut().unwrap() += calories,
+            Err(_) => elves.push(0),
+        }
+    }
+    let mut top = [0; 3];
+    for calories in elves {
+        if let Some(i) = top.iter().position(|t| calories > *t) {
+            top[i..].rotate_right(1);
+            top[i] = calories;
+        }
+    }
+    (top[0].into(), top.iter().sum::<i64>().into())
+}
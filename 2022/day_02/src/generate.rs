@@ -0,0 +1,31 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A strategy guide of `size` rounds
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.pick(&['A', 'B', 'C']),
+                rng.pick(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect()
+}
+
+// Shapes are 0, 1 and 2 for rock, paper and scissors, and each shape beats
+// the one before it
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let (mut guessed, mut planned) = (0, 0);
+    for line in input {
+        let bytes = line.as_bytes();
+        let (opponent, column) = ((bytes[0] - b'A') as i64, (bytes[2] - b'X') as i64);
+        let outcome = |me: i64| (me - opponent + 1).rem_euclid(3);
+
+        guessed += column + 1 + 3 * outcome(column);
+        let me = (0..3).find(|me| outcome(*me) == column).unwrap();
+        planned += me + 1 + 3 * column;
+    }
+    (guessed.into(), planned.into())
+}
@@ -0,0 +1,61 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const ITEMS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// `size` groups of three rucksacks. Each rucksack has exactly one item in
+/// both compartments, and each group exactly one item in all three rucksacks
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for _ in 0..size.max(1) {
+        let mut items: Vec<char> = ITEMS.chars().collect();
+        rng.shuffle(&mut items);
+        let badge = items[0];
+
+        // Apart from the badge, every rucksack in the group has its own items
+        for own in items[1..].chunks(17) {
+            let mut pool = own.to_vec();
+            pool.push(badge);
+            rng.shuffle(&mut pool);
+            let shared = pool[0];
+            let split = rng.range(1..pool.len() as i64 - 1) as usize;
+            let (first, second) = pool[1..].split_at(split);
+
+            let length = rng.range(4..16) as usize;
+            let mut compartment = |only: &[char]| {
+                let mut c = vec![shared];
+                c.extend(only.iter().filter(|i| **i == badge));
+                while c.len() < length {
+                    c.push(*rng.pick(only));
+                }
+                rng.shuffle(&mut c);
+                c.into_iter().collect::<String>()
+            };
+            let line = compartment(first) + &compartment(second);
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+fn items(s: &str) -> u64 {
+    s.chars()
+        .fold(0, |set, c| set | 1 << ITEMS.find(c).unwrap())
+}
+
+// Items are bits in a set, with the priority being the bit position plus one
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let priority = |set: u64| set.trailing_zeros() as u64 + 1;
+    let misplaced: u64 = input
+        .iter()
+        .map(|l| {
+            let (first, second) = l.split_at(l.len() / 2);
+            priority(items(first) & items(second))
+        })
+        .sum();
+    let badges: u64 = input
+        .chunks(3)
+        .map(|group| priority(group.iter().fold(u64::MAX, |set, l| set & items(l))))
+        .sum();
+    (misplaced.into(), badges.into())
+}
@@ -1,6 +1,11 @@
 use std::collections::HashSet;
 use std::str::FromStr;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 pub struct Rucksack {
     first: String,
     second: String,
@@ -31,43 +36,58 @@ impl RucksackItemScore for char {
     }
 }
 
-fn task_one(input: &[String]) -> usize {
-    input.into_iter().fold(0, |acc, rucksack| {
-        let rucksack = Rucksack::from_str(rucksack).unwrap();
-        for c1 in rucksack.first.chars() {
-            for c2 in rucksack.second.chars() {
-                if c2 == c1 {
-                    return c1.rucksack_item_score() + acc;
+fn task_one(input: &[String]) -> Answer {
+    input
+        .into_iter()
+        .fold(0, |acc, rucksack| {
+            let rucksack = Rucksack::from_str(rucksack).unwrap();
+            for c1 in rucksack.first.chars() {
+                for c2 in rucksack.second.chars() {
+                    if c2 == c1 {
+                        return c1.rucksack_item_score() + acc;
+                    }
                 }
             }
-        }
-        acc
-    })
+            acc
+        })
+        .into()
 }
 
-fn task_two(input: &[String]) -> usize {
-    input.chunks(3).into_iter().fold(0, |acc, group| {
-        let first: HashSet<char> = group.get(0).unwrap().chars().collect();
-        let second: HashSet<char> = group.get(1).unwrap().chars().collect();
-        let third: HashSet<char> = group.get(2).unwrap().chars().collect();
+fn task_two(input: &[String]) -> Answer {
+    input
+        .chunks(3)
+        .into_iter()
+        .fold(0, |acc, group| {
+            let first: HashSet<char> = group.get(0).unwrap().chars().collect();
+            let second: HashSet<char> = group.get(1).unwrap().chars().collect();
+            let third: HashSet<char> = group.get(2).unwrap().chars().collect();
 
-        first
-            .intersection(&second)
-            .map(|c| c.to_owned())
-            .collect::<HashSet<char>>()
-            .intersection(&third)
-            .collect::<Vec<&char>>()
-            .pop()
-            .unwrap()
-            .rucksack_item_score()
-            + acc
-    })
+            first
+                .intersection(&second)
+                .map(|c| c.to_owned())
+                .collect::<HashSet<char>>()
+                .intersection(&third)
+                .collect::<Vec<&char>>()
+                .pop()
+                .unwrap()
+                .rucksack_item_score()
+                + acc
+        })
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -81,26 +101,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,26 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` pairs of section assignments
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut range = || {
+        let (a, b) = (rng.range(1..100), rng.range(1..100));
+        format!("{}-{}", a.min(b), a.max(b))
+    };
+    (0..size)
+        .map(|_| format!("{},{}", range(), range()))
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let (mut contained, mut overlapping) = (0, 0);
+    for line in input {
+        let v: Vec<i64> = line.split([',', '-']).map(|v| v.parse().unwrap()).collect();
+        let sections =
+            |start: i64, end: i64| (start..=end).collect::<std::collections::HashSet<_>>();
+        let (first, second) = (sections(v[0], v[1]), sections(v[2], v[3]));
+        contained += (first.is_subset(&second) || second.is_subset(&first)) as usize;
+        overlapping += (!first.is_disjoint(&second)) as usize;
+    }
+    (contained.into(), overlapping.into())
+}
@@ -0,0 +1,89 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A drawing of up to nine stacks followed by `size` moves. No move takes a
+/// stack's last crate, so every stack has a crate on top at the end
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut stacks: Vec<Vec<char>> = (0..rng.range(1..10))
+        .map(|_| {
+            (0..rng.range(1..8))
+                .map(|_| (b'A' + rng.below(26) as u8) as char)
+                .collect()
+        })
+        .collect();
+    let tallest = stacks.iter().map(Vec::len).max().unwrap();
+
+    let mut lines: Vec<String> = (0..tallest)
+        .rev()
+        .map(|level| {
+            let row: Vec<String> = stacks
+                .iter()
+                .map(|s| {
+                    s.get(level)
+                        .map_or("   ".to_string(), |c| format!("[{}]", c))
+                })
+                .collect();
+            row.join(" ")
+        })
+        .collect();
+    let labels: Vec<String> = (1..=stacks.len()).map(|i| format!(" {} ", i)).collect();
+    lines.push(labels.join(" "));
+    lines.push(String::new());
+
+    if stacks.len() < 2 {
+        return lines;
+    }
+    for _ in 0..size {
+        let from = rng.below(stacks.len());
+        if stacks[from].len() < 2 {
+            continue;
+        }
+        let to = (from + 1 + rng.below(stacks.len() - 1)) % stacks.len();
+        let num = rng.range(1..stacks[from].len() as i64) as usize;
+        let keep = stacks[from].len() - num;
+        let moved = stacks[from].split_off(keep);
+        stacks[to].extend(moved);
+        lines.push(format!("move {} from {} to {}", num, from + 1, to + 1));
+    }
+    lines
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let blank = input.iter().position(|l| l.is_empty()).unwrap();
+    let labels = &input[blank - 1];
+    let columns: Vec<usize> = labels
+        .char_indices()
+        .filter(|(_, c)| c.is_ascii_digit())
+        .map(|(i, _)| i)
+        .collect();
+
+    let stacks: Vec<Vec<char>> = columns
+        .iter()
+        .map(|col| {
+            input[..blank - 1]
+                .iter()
+                .rev()
+                .filter_map(|l| l.chars().nth(*col).filter(char::is_ascii_uppercase))
+                .collect()
+        })
+        .collect();
+
+    let arrange = |in_order: bool| {
+        let mut stacks = stacks.clone();
+        for line in &input[blank + 1..] {
+            let v: Vec<usize> = line.split(' ').filter_map(|w| w.parse().ok()).collect();
+            let (num, from, to) = (v[0], v[1] - 1, v[2] - 1);
+            let keep = stacks[from].len() - num;
+            let mut moved = stacks[from].split_off(keep);
+            if !in_order {
+                moved.reverse();
+            }
+            stacks[to].extend(moved);
+        }
+        stacks
+            .iter()
+            .map(|s| *s.last().unwrap())
+            .collect::<String>()
+    };
+    (arrange(false).into(), arrange(true).into())
+}
@@ -2,6 +2,11 @@ use std::{collections::HashMap, str::FromStr};
 
 use itertools::Itertools;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Debug)]
 struct Command {
     num: u32,
@@ -105,22 +110,30 @@ impl From<&[String]> for Crates {
     }
 }
 
-fn task_one(input: &[String]) -> String {
+fn task_one(input: &[String]) -> Answer {
     let mut crates: Crates = input.into();
     crates.crate_mover_9000();
-    crates.get_answer()
+    crates.get_answer().into()
 }
 
-fn task_two(input: &[String]) -> String {
+fn task_two(input: &[String]) -> Answer {
     let mut crates: Crates = input.into();
     crates.crate_mover_9001();
-    crates.get_answer()
+    crates.get_answer().into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -134,26 +147,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,30 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A datastream of about `size` characters. Most of it draws on few letters
+/// so markers show up late, and it ends with fourteen distinct letters so
+/// both markers are always there
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let letters: Vec<char> = ('a'..='z').collect();
+    let alphabet = &letters[..rng.range(4..16) as usize];
+    let mut stream: String = (0..size).map(|_| *rng.pick(alphabet)).collect();
+
+    let mut tail = letters.clone();
+    rng.shuffle(&mut tail);
+    stream.extend(&tail[..14]);
+    vec![stream]
+}
+
+fn marker(stream: &[char], length: usize) -> usize {
+    (length..=stream.len())
+        .find(|end| {
+            let window = &stream[end - length..*end];
+            (0..length).all(|i| (i + 1..length).all(|j| window[i] != window[j]))
+        })
+        .unwrap()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let stream: Vec<char> = input[0].chars().collect();
+    (marker(&stream, 4).into(), marker(&stream, 14).into())
+}
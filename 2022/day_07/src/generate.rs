@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const DISK: u64 = 70_000_000;
+const NEEDED: u64 = 30_000_000;
+
+#[derive(Default)]
+struct Dir {
+    name: String,
+    files: Vec<(String, u64)>,
+    children: Vec<usize>,
+}
+
+/// A terminal session exploring a tree of `size` directories, each listed
+/// once. The files use between 40 and 70 million, so some space always needs
+/// freeing
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut dirs = vec![Dir::default()];
+    for i in 1..size.max(1) {
+        let parent = rng.below(i);
+        dirs[parent].children.push(i);
+        dirs.push(Dir {
+            name: format!("d{}", i),
+            ..Default::default()
+        });
+    }
+
+    let mut total = 0;
+    for _ in 0..size * 2 {
+        let size = rng.range(1..60_000) as u64;
+        total += add_file(rng, &mut dirs, size);
+    }
+    let target = rng.range(41_000_000..69_000_000) as u64;
+    while total < target {
+        let size = (rng.range(1_000_000..8_000_000) as u64).min(target - total + 1);
+        total += add_file(rng, &mut dirs, size);
+    }
+
+    let mut lines = vec!["$ cd /".to_string()];
+    transcript(&dirs, 0, &mut lines);
+    lines
+}
+
+fn add_file(rng: &mut Rng, dirs: &mut [Dir], size: u64) -> u64 {
+    let dir = rng.below(dirs.len());
+    let ext = *rng.pick(&["", ".txt", ".dat", ".log"]);
+    let name = format!("f{}{}", dirs[dir].files.len(), ext);
+    dirs[dir].files.push((name, size));
+    size
+}
+
+fn transcript(dirs: &[Dir], dir: usize, lines: &mut Vec<String>) {
+    lines.push("$ ls".to_string());
+    let children = &dirs[dir].children;
+    lines.extend(children.iter().map(|c| format!("dir {}", dirs[*c].name)));
+    lines.extend(
+        dirs[dir]
+            .files
+            .iter()
+            .map(|(name, size)| format!("{} {}", size, name)),
+    );
+    for child in children {
+        lines.push(format!("$ cd {}", dirs[*child].name));
+        transcript(dirs, *child, lines);
+        lines.push("$ cd ..".to_string());
+    }
+}
+
+// Collects every file under its full path, then sizes each directory by the
+// files whose path starts with it
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let mut cwd: Vec<&str> = Vec::new();
+    let mut dirs = vec![String::from("/")];
+    let mut files: HashMap<String, u64> = HashMap::new();
+    for line in input {
+        let words: Vec<&str> = line.split(' ').collect();
+        match words[..] {
+            ["$", "cd", "/"] => cwd.clear(),
+            ["$", "cd", ".."] => {
+                cwd.pop();
+            }
+            ["$", "cd", name] => {
+                cwd.push(name);
+                dirs.push(format!("/{}/", cwd.join("/")));
+            }
+            ["$", "ls"] | ["dir", _] => {}
+            [size, name] => {
+                let path = cwd.iter().fold(String::from("/"), |p, d| p + d + "/") + name;
+                files.insert(path, size.parse().unwrap());
+            }
+            _ => panic!("unexpected line {}", line),
+        }
+    }
+
+    let sizes: Vec<u64> = dirs
+        .iter()
+        .map(|dir| {
+            files
+                .iter()
+                .filter(|(path, _)| path.starts_with(dir))
+                .map(|(_, size)| size)
+                .sum()
+        })
+        .collect();
+    let small: u64 = sizes.iter().filter(|s| **s <= 100_000).sum();
+    let missing = NEEDED - (DISK - sizes[0]);
+    let freed = *sizes.iter().filter(|s| **s >= missing).min().unwrap();
+    (small.into(), freed.into())
+}
@@ -2,6 +2,11 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Debug)]
 enum CdArg {
     Home,
@@ -115,19 +120,24 @@ impl FromStr for Entry {
     }
 }
 
-fn task_one(input: &[String]) -> usize {
+fn task_one(input: &[String]) -> Answer {
     let filetree = FileTree::new(input);
-    filetree.dir.keys().into_iter().fold(0, |acc, dir| {
-        let size = filetree.dir.get(dir).unwrap();
-        if size < &100000 {
-            acc + *size as usize
-        } else {
-            acc
-        }
-    })
+    filetree
+        .dir
+        .keys()
+        .into_iter()
+        .fold(0, |acc, dir| {
+            let size = filetree.dir.get(dir).unwrap();
+            if size < &100000 {
+                acc + *size as usize
+            } else {
+                acc
+            }
+        })
+        .into()
 }
 
-fn task_two(input: &[String]) -> u64 {
+fn task_two(input: &[String]) -> Answer {
     let filetree = FileTree::new(input);
 
     let total_available = 70000000;
@@ -146,13 +156,21 @@ fn task_two(input: &[String]) -> u64 {
         }
     });
 
-    delete
+    delete.into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -166,26 +184,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,46 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A square grid of tree heights, `size` trees on a side
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let tallest = rng.range(1..10) as u8;
+    (0..size.max(1))
+        .map(|_| {
+            (0..size.max(1))
+                .map(|_| (b'0' + rng.below(tallest as usize + 1) as u8) as char)
+                .collect()
+        })
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let grid: Vec<Vec<u8>> = input.iter().map(|l| l.bytes().collect()).collect();
+    let (height, width) = (grid.len() as i64, grid[0].len() as i64);
+
+    let (mut visible, mut best) = (0, 0);
+    for y in 0..height {
+        for x in 0..width {
+            let tree = grid[y as usize][x as usize];
+            let mut seen = false;
+            let mut score = 1;
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                let (mut cx, mut cy, mut distance) = (x + dx, y + dy, 0);
+                let mut blocked = false;
+                while (0..width).contains(&cx) && (0..height).contains(&cy) {
+                    distance += 1;
+                    if grid[cy as usize][cx as usize] >= tree {
+                        blocked = true;
+                        break;
+                    }
+                    cx += dx;
+                    cy += dy;
+                }
+                seen |= !blocked;
+                score *= distance;
+            }
+            visible += seen as usize;
+            best = best.max(score);
+        }
+    }
+    (visible.into(), best.into())
+}
@@ -0,0 +1,55 @@
+use std::collections::HashSet;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` head motions, a few of them diagonal
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let direction = if rng.chance(0.1) {
+                *rng.pick(&["UR", "UL", "DR", "DL"])
+            } else {
+                *rng.pick(&["U", "D", "L", "R"])
+            };
+            format!("{} {}", direction, rng.range(1..12))
+        })
+        .collect()
+}
+
+// Moves every knot after every step, without stopping at the first knot that
+// stays put
+fn tail_positions(input: &[String], knots: usize) -> usize {
+    let mut rope = vec![(0i64, 0i64); knots];
+    let mut visited = HashSet::from([(0, 0)]);
+    for line in input {
+        let (direction, steps) = line.split_once(' ').unwrap();
+        let (mut dx, mut dy) = (0, 0);
+        for c in direction.chars() {
+            match c {
+                'U' => dy -= 1,
+                'D' => dy += 1,
+                'L' => dx -= 1,
+                _ => dx += 1,
+            }
+        }
+        for _ in 0..steps.parse().unwrap() {
+            rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+            for k in 1..knots {
+                let (x, y) = (rope[k - 1].0 - rope[k].0, rope[k - 1].1 - rope[k].1);
+                if x.abs() > 1 || y.abs() > 1 {
+                    rope[k] = (rope[k].0 + x.signum(), rope[k].1 + y.signum());
+                }
+            }
+            visited.insert(rope[knots - 1]);
+        }
+    }
+    visited.len()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    (
+        tail_positions(input, 2).into(),
+        tail_positions(input, 10).into(),
+    )
+}
@@ -1,183 +1,160 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
-};
+use std::{collections::HashSet, str::FromStr};
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
 
 #[derive(Debug)]
-enum Direction {
-    Up(i32),
-    Down(i32),
-    Left(i32),
-    Right(i32),
+struct Motion {
+    direction: (i32, i32),
+    steps: i32,
 }
 
-impl FromStr for Direction {
-    type Err = ();
+impl FromStr for Motion {
+    type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.split_once(' ').unwrap() {
-            ("U", num) => Ok(Direction::Up(num.parse().unwrap())),
-            ("D", num) => Ok(Direction::Down(num.parse().unwrap())),
-            ("L", num) => Ok(Direction::Left(num.parse().unwrap())),
-            ("R", num) => Ok(Direction::Right(num.parse().unwrap())),
-            _ => unreachable!(),
+        let (dir, num) = s.split_once(' ').ok_or(format!("Invalid motion {}", s))?;
+        // Combining a vertical and a horizontal letter gives a diagonal move,
+        // e.g. `UR 3`. The knots only follow moves of one cell at a time, so
+        // anything more is refused
+        let direction = dir.chars().try_fold((0, 0), |(x, y), c| match c {
+            'U' | 'D' if y != 0 => Err(format!("Invalid direction {}", dir)),
+            'L' | 'R' if x != 0 => Err(format!("Invalid direction {}", dir)),
+            'U' => Ok((x, -1)),
+            'D' => Ok((x, 1)),
+            'L' => Ok((-1, y)),
+            'R' => Ok((1, y)),
+            c => Err(format!("Invalid direction {}", c)),
+        })?;
+        if direction == (0, 0) {
+            return Err(format!("Invalid motion {}", s));
         }
+        Ok(Self {
+            direction,
+            steps: num.parse().map_err(|_| format!("Invalid steps {}", num))?,
+        })
     }
 }
 
 struct Rope {
-    visited_tail: HashSet<(i32, i32)>,
-    knot_map: HashMap<i32, (i32, i32)>,
-    knots: i32,
+    knots: Vec<(i32, i32)>,
+    visited: Vec<HashSet<(i32, i32)>>,
+    trails: Vec<Vec<(i32, i32)>>,
 }
 
 impl Rope {
-    fn new(knots: i32) -> Self {
-        let mut set = HashSet::new();
-        let mut map = HashMap::new();
-        for i in 0..knots {
-            map.insert(i, (0, 0));
-        }
-        set.insert((0, 0));
+    fn new(knots: usize) -> Self {
         Self {
-            visited_tail: set,
-            knot_map: map,
-            knots,
+            knots: vec![(0, 0); knots],
+            visited: vec![HashSet::from([(0, 0)]); knots],
+            trails: vec![vec![(0, 0)]; knots],
         }
     }
 
-    fn touching(&self, curr: i32, next: i32) -> bool {
-        let curr = self.knot_map.get(&curr).unwrap();
-        let next = self.knot_map.get(&next).unwrap();
-        curr == next
-            || next.0 == curr.0 + 1 && next.1 == curr.1
-            || next.0 == curr.0 - 1 && next.1 == curr.1
-            || next.0 == curr.0 && next.1 == curr.1 + 1
-            || next.0 == curr.0 && next.1 == curr.1 - 1
-            || next.0 == curr.0 - 1 && next.1 == curr.1 + 1
-            || next.0 == curr.0 + 1 && next.1 == curr.1 + 1
-            || next.0 == curr.0 + 1 && next.1 == curr.1 - 1
-            || next.0 == curr.0 - 1 && next.1 == curr.1 - 1
+    fn record(&mut self, knot: usize) {
+        let pos = self.knots[knot];
+        self.visited[knot].insert(pos);
+        self.trails[knot].push(pos);
     }
 
-    // Returns an optional direction tail should walk in if head is straight ahead
-    fn straight(&self, curr: i32, next: i32) -> Option<Direction> {
-        let curr = self.knot_map.get(&curr).unwrap();
-        let next = self.knot_map.get(&next).unwrap();
-
-        if next.0 == curr.0 - 2 && next.1 == curr.1 {
-            return Some(Direction::Right(0));
-        } else if next.0 == curr.0 + 2 && next.1 == curr.1 {
-            return Some(Direction::Left(0));
-        } else if next.0 == curr.0 && next.1 == curr.1 - 2 {
-            return Some(Direction::Down(0));
-        } else if next.0 == curr.0 && next.1 == curr.1 + 2 {
-            return Some(Direction::Up(0));
+    fn step(&mut self, (dx, dy): (i32, i32)) {
+        self.knots[0].0 += dx;
+        self.knots[0].1 += dy;
+        self.record(0);
+
+        for knot in 1..self.knots.len() {
+            let (hx, hy) = self.knots[knot - 1];
+            let (tx, ty) = self.knots[knot];
+            let (dx, dy) = (hx - tx, hy - ty);
+            // Still touching, and then nothing further back can move either
+            if dx.abs() <= 1 && dy.abs() <= 1 {
+                break;
+            }
+            self.knots[knot] = (tx + dx.signum(), ty + dy.signum());
+            self.record(knot);
         }
-        None
     }
 
-    // Returns an optional direction tail should walk in if head is diagonal
-    fn diagonally(&self, curr: i32, next: i32) -> Option<(Direction, Direction)> {
-        let curr = self.knot_map.get(&curr).unwrap();
-        let next = self.knot_map.get(&next).unwrap();
-        if next.0 == curr.0 - 1 && next.1 == curr.1 + 2
-            || next.0 == curr.0 - 2 && next.1 == curr.1 + 1
-            || next.0 == curr.0 - 2 && next.1 == curr.1 + 2
-        {
-            return Some((Direction::Right(0), Direction::Up(0)));
-        } else if next.0 == curr.0 + 1 && next.1 == curr.1 + 2
-            || next.0 == curr.0 + 2 && next.1 == curr.1 + 1
-            || next.0 == curr.0 + 2 && next.1 == curr.1 + 2
-        {
-            return Some((Direction::Left(0), Direction::Up(0)));
-        } else if next.0 == curr.0 - 1 && next.1 == curr.1 - 2
-            || next.0 == curr.0 - 2 && next.1 == curr.1 - 1
-            || next.0 == curr.0 - 2 && next.1 == curr.1 - 2
-        {
-            return Some((Direction::Right(0), Direction::Down(0)));
-        } else if next.0 == curr.0 + 2 && next.1 == curr.1 - 1
-            || next.0 == curr.0 + 1 && next.1 == curr.1 - 2
-            || next.0 == curr.0 + 2 && next.1 == curr.1 - 2
-        {
-            return Some((Direction::Left(0), Direction::Down(0)));
-        } else {
-            None
+    fn r#move(&mut self, motion: &Motion) {
+        for _ in 0..motion.steps {
+            self.step(motion.direction);
+            if render() {
+                println!("{}\n", self.render());
+            }
         }
     }
 
-    fn move_knot(&mut self, knot: i32, direction: &Direction) {
-        let knot = self.knot_map.get_mut(&knot).unwrap();
-        match direction {
-            Direction::Up(_) => knot.1 -= 1,
-            Direction::Down(_) => knot.1 += 1,
-            Direction::Left(_) => knot.0 -= 1,
-            Direction::Right(_) => knot.0 += 1,
-        };
-    }
-
-    fn knot(&self, knot: i32) -> &(i32, i32) {
-        self.knot_map.get(&knot).unwrap()
+    fn tail(&self) -> usize {
+        self.knots.len() - 1
     }
 
-    fn r#move(&mut self, num: i32, direction: &Direction) {
-        for _ in 0..num {
-            self.move_knot(0, direction);
-            for knot in 0..self.knots - 1 {
-                let next = knot + 1;
-                if !self.touching(knot, next) {
-                    if let Some(straight) = self.straight(knot, next) {
-                        self.move_knot(next, &straight);
-                        if next == self.knots - 1 {
-                            self.visited_tail.insert(*self.knot(next));
-                        }
-                    } else if let Some(diagonally) = self.diagonally(knot, next) {
-                        self.move_knot(next, &diagonally.0);
-                        self.move_knot(next, &diagonally.1);
-                        if next == self.knots - 1 {
-                            self.visited_tail.insert(*self.knot(next));
-                        }
-                    }
-                }
-            }
-        }
+    fn render(&self) -> String {
+        let points = self.knots.iter().chain(std::iter::once(&(0, 0)));
+        let min_x = points.clone().map(|p| p.0).min().unwrap();
+        let max_x = points.clone().map(|p| p.0).max().unwrap();
+        let min_y = points.clone().map(|p| p.1).min().unwrap();
+        let max_y = points.map(|p| p.1).max().unwrap();
+
+        (min_y..=max_y)
+            .map(|y| {
+                (min_x..=max_x)
+                    .map(|x| match self.knots.iter().position(|k| *k == (x, y)) {
+                        Some(0) => 'H',
+                        Some(knot) => std::char::from_digit(knot as u32 % 36, 36).unwrap(),
+                        None if (x, y) == (0, 0) => 's',
+                        None => '.',
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
     }
 }
 
-impl Direction {
-    fn walk(&self, rope: &mut Rope) {
-        match self {
-            Direction::Up(num) => rope.r#move(*num, self),
-            Direction::Down(num) => rope.r#move(*num, self),
-            Direction::Left(num) => rope.r#move(*num, self),
-            Direction::Right(num) => rope.r#move(*num, self),
-        };
-    }
-}
-
-fn task_one(input: &[String]) -> usize {
-    let mut rope = Rope::new(2);
-    input.into_iter().for_each(|s| {
-        let dir = Direction::from_str(s).unwrap();
-        dir.walk(&mut rope);
+fn simulate(input: &[String], knots: usize) -> Rope {
+    let mut rope = Rope::new(knots);
+    input.iter().for_each(|s| {
+        let motion = Motion::from_str(s).unwrap();
+        rope.r#move(&motion);
     });
 
-    rope.visited_tail.into_iter().count()
+    match get_trail().map(|knot| (knot, rope.trails.get(knot))) {
+        Some((_, Some(trail))) => {
+            let trail = trail
+                .iter()
+                .map(|(x, y)| format!("{},{}", x, y))
+                .collect::<Vec<_>>();
+            println!("{}", trail.join(" "));
+        }
+        Some((knot, None)) => eprintln!("TRAIL={} skipped, the rope has {} knots", knot, knots),
+        None => (),
+    }
+    rope
 }
 
-fn task_two(input: &[String]) -> usize {
-    let mut rope = Rope::new(10);
-    input.into_iter().for_each(|s| {
-        let dir = Direction::from_str(s).unwrap();
-        dir.walk(&mut rope);
-    });
+fn task_one(input: &[String]) -> Answer {
+    let rope = simulate(input, 2);
+    rope.visited[rope.tail()].len().into()
+}
 
-    rope.visited_tail.into_iter().count()
+fn task_two(input: &[String]) -> Answer {
+    let rope = simulate(input, 10);
+    rope.visited[rope.tail()].len().into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -191,26 +168,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -220,3 +192,11 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn render() -> bool {
+    std::env::var("RENDER").is_ok()
+}
+
+fn get_trail() -> Option<usize> {
+    std::env::var("TRAIL").ok().map(|k| k.parse().unwrap())
+}
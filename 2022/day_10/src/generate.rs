@@ -0,0 +1,60 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const CYCLES: usize = 240;
+
+/// A program that runs for at least 240 cycles, with `size` instructions
+/// more after that. The register stays roughly on the screen
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let (mut lines, mut cycles, mut x, mut extra) = (Vec::new(), 0, 1, 0);
+    while cycles < CYCLES || extra < size {
+        if cycles >= CYCLES {
+            extra += 1;
+        }
+        if rng.chance(0.3) {
+            lines.push("noop".to_string());
+            cycles += 1;
+        } else {
+            let v = rng.range(-8..9).clamp(-5 - x, 45 - x);
+            x += v;
+            lines.push(format!("addx {}", v));
+            cycles += 2;
+        }
+    }
+    lines
+}
+
+// The register during every cycle, counting from cycle one
+fn register(input: &[String]) -> Vec<i64> {
+    let mut x = 1;
+    let mut during = Vec::new();
+    for line in input {
+        during.push(x);
+        if let Some(v) = line.strip_prefix("addx ") {
+            during.push(x);
+            x += v.parse::<i64>().unwrap();
+        }
+    }
+    during
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let during = register(input);
+    let strength: i64 = (20..=220)
+        .step_by(40)
+        .map(|c| c as i64 * during[c - 1])
+        .sum();
+    let rows = during[..CYCLES].chunks(40).map(|row| {
+        row.iter()
+            .enumerate()
+            .map(|(col, x)| {
+                if (x - col as i64).abs() <= 1 {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>()
+    });
+    (strength.into(), Answer::glyphs(rows))
+}
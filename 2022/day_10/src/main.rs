@@ -1,5 +1,10 @@
 use std::{collections::HashMap, str::FromStr};
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Debug)]
 enum Instruction {
     Addx(i32),
@@ -74,7 +79,7 @@ impl FromStr for Instruction {
     }
 }
 
-fn task_one(input: &[String]) -> i32 {
+fn task_one(input: &[String]) -> Answer {
     let mut cpu = Cpu::new(input);
     cpu.run(220, None);
     [20, 60, 100, 140, 180, 220]
@@ -82,22 +87,34 @@ fn task_one(input: &[String]) -> i32 {
         .fold(0, |acc, num| {
             acc + (*cpu.register_map.get(&num).unwrap() * num)
         })
+        .into()
 }
 
-fn task_two(input: &[String]) -> String {
+fn task_two(input: &[String]) -> Answer {
     let mut vec = vec!['.'; 240];
     vec[0] = '#';
     let mut crt = Crt { vec, dim: (40, 6) };
     let mut cpu = Cpu::new(input);
     cpu.run(239, Some(&mut crt));
-    crt.print();
-    "PZBGZEJB".to_string()
+    Answer::glyphs(
+        crt.vec
+            .chunks(crt.dim.0 as usize)
+            .map(|row| row.iter().collect::<String>()),
+    )
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -111,26 +128,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
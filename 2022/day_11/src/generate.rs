@@ -0,0 +1,135 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+// The tests always use distinct small primes, which keeps the product small
+// enough to square in a u64
+const PRIMES: [u64; 8] = [2, 3, 5, 7, 11, 13, 17, 19];
+
+/// Between two and eight monkeys holding `size` items between them. Notes
+/// where worry outgrows a u64 with relief are redrawn, as the puzzle never
+/// lets that happen
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    loop {
+        let notes = notes(rng, size);
+        if business(&notes, 20, true).is_some() {
+            return notes;
+        }
+    }
+}
+
+fn notes(rng: &mut Rng, size: usize) -> Vec<String> {
+    let count = rng.range(2..9) as usize;
+    let mut primes = PRIMES.to_vec();
+    rng.shuffle(&mut primes);
+
+    let mut items = vec![Vec::new(); count];
+    for _ in 0..size.max(1) {
+        items[rng.below(count)].push(rng.range(40..100).to_string());
+    }
+
+    let mut lines = Vec::new();
+    for monkey in 0..count {
+        let operation = match rng.below(3) {
+            0 => "old * old".to_string(),
+            1 => format!("old * {}", rng.range(2..20)),
+            _ => format!("old + {}", rng.range(1..10)),
+        };
+        let mut other = || (monkey + 1 + rng.below(count - 1)) % count;
+        let (if_true, if_false) = (other(), other());
+        if monkey > 0 {
+            lines.push(String::new());
+        }
+        lines.extend([
+            format!("Monkey {}:", monkey),
+            // Real notes list at least one item, so an empty hand gets one
+            format!(
+                "  Starting items: {}",
+                if items[monkey].is_empty() {
+                    "1".to_string()
+                } else {
+                    items[monkey].join(", ")
+                }
+            ),
+            format!("  Operation: new = {}", operation),
+            format!("  Test: divisible by {}", primes[monkey]),
+            format!("    If true: throw to monkey {}", if_true),
+            format!("    If false: throw to monkey {}", if_false),
+        ]);
+    }
+    lines
+}
+
+struct Monkey {
+    items: Vec<u128>,
+    square: bool,
+    times: u128,
+    plus: u128,
+    divisor: u128,
+    targets: [usize; 2],
+}
+
+fn last_number(line: &str) -> u128 {
+    line.rsplit(' ').next().unwrap().parse().unwrap()
+}
+
+// Worry is kept modulo the product of the divisors without relief, and
+// gives up once it outgrows a u64 with it
+fn business(input: &[String], rounds: usize, relieved: bool) -> Option<u64> {
+    let mut monkeys: Vec<Monkey> = input
+        .split(|l| l.is_empty())
+        .map(|notes| {
+            let operation = notes[2].rsplit_once("old ").unwrap().1;
+            let (op, operand) = operation.split_once(' ').unwrap();
+            let operand = operand.parse().unwrap_or(0);
+            Monkey {
+                items: notes[1]
+                    .split(": ")
+                    .nth(1)
+                    .unwrap()
+                    .split(", ")
+                    .map(|i| i.parse().unwrap())
+                    .collect(),
+                square: operation == "* old",
+                times: if op == "*" { operand } else { 1 },
+                plus: if op == "+" { operand } else { 0 },
+                divisor: last_number(&notes[3]),
+                targets: [
+                    last_number(&notes[5]) as usize,
+                    last_number(&notes[4]) as usize,
+                ],
+            }
+        })
+        .collect();
+    let modulus: u128 = monkeys.iter().map(|m| m.divisor).product();
+
+    let mut inspected = vec![0; monkeys.len()];
+    for _ in 0..rounds {
+        for m in 0..monkeys.len() {
+            for item in std::mem::take(&mut monkeys[m].items) {
+                inspected[m] += 1;
+                let monkey = &monkeys[m];
+                let worry = if monkey.square {
+                    item * item
+                } else {
+                    item * monkey.times + monkey.plus
+                };
+                let worry = if relieved { worry / 3 } else { worry % modulus };
+                if worry > u64::MAX as u128 {
+                    return None;
+                }
+                let divisible = worry % monkey.divisor == 0;
+                let target = monkey.targets[divisible as usize];
+                monkeys[target].items.push(worry);
+            }
+        }
+    }
+    inspected.sort_unstable();
+    Some(inspected.iter().rev().take(2).product())
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    (
+        business(input, 20, true).unwrap().into(),
+        business(input, 10000, false).unwrap().into(),
+    )
+}
@@ -1,15 +1,65 @@
-use std::{collections::VecDeque, str::FromStr};
+use num_bigint::{BigInt, BigUint};
+use std::{
+    collections::VecDeque,
+    iter::Peekable,
+    ops::{Add, Div, Mul, Rem, Sub},
+    str::{Chars, FromStr},
+};
 
-#[derive(Debug)]
-enum Operation {
-    Multiply { first: Value, second: Value },
-    Add { first: Value, second: Value },
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+trait Worry:
+    Clone
+    + PartialOrd
+    + From<u64>
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    fn is_divisible(&self, divisor: u64) -> bool {
+        self.clone() % Self::from(divisor) == Self::from(0)
+    }
+
+    // Rounds down like the notes say, which `/` only does above zero
+    fn divide_down(self, divisor: u64) -> Self {
+        let divisor = Self::from(divisor);
+        let quotient = self.clone() / divisor.clone();
+        if self % divisor < Self::from(0) {
+            quotient - Self::from(1)
+        } else {
+            quotient
+        }
+    }
 }
 
-#[derive(Debug)]
-enum Value {
+impl Worry for u128 {}
+impl Worry for BigInt {}
+
+#[derive(Debug, Clone, Copy)]
+enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
     Old,
     Num(u64),
+    BinOp(BinOp, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Relief {
+    DivideBy(u64),
+    ModuloLcm,
+    None,
 }
 
 #[derive(Debug)]
@@ -20,42 +70,155 @@ struct Test {
 }
 
 #[derive(Debug)]
-struct Monkey {
-    items: VecDeque<u64>,
+struct Monkey<W> {
+    items: VecDeque<W>,
     test: Test,
-    operation: Operation,
+    operation: Expr,
     inspected: u64,
 }
 
 #[derive(Debug)]
-struct Monkeys {
-    monkeys: Vec<Monkey>,
+struct Monkeys<W> {
+    monkeys: Vec<Monkey<W>>,
 }
 
-impl FromStr for Value {
-    type Err = ();
+#[derive(Debug, Default)]
+struct RoundStats {
+    held: Vec<usize>,
+    inspections: Vec<u64>,
+    throws: Vec<Vec<u64>>,
+}
+
+impl BinOp {
+    fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Add | BinOp::Sub => 1,
+            BinOp::Mul | BinOp::Div => 2,
+        }
+    }
+
+    fn apply<W: Worry>(&self, a: W, b: W) -> W {
+        match self {
+            BinOp::Add => a + b,
+            BinOp::Sub => a - b,
+            BinOp::Mul => a * b,
+            BinOp::Div => a / b,
+        }
+    }
+}
+
+fn skip_whitespace(iter: &mut Peekable<Chars>) {
+    while iter.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn parse_atom(iter: &mut Peekable<Chars>) -> Result<Expr, String> {
+    skip_whitespace(iter);
+    match iter.peek() {
+        Some('(') => {
+            iter.next();
+            let expr = parse_expr(iter, 0)?;
+            skip_whitespace(iter);
+            match iter.next() {
+                Some(')') => Ok(expr),
+                c => Err(format!("Expected ')', found {:?}", c)),
+            }
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let mut num = String::new();
+            while let Some(d) = iter.next_if(|c| c.is_ascii_digit()) {
+                num.push(d);
+            }
+            Ok(Expr::Num(num.parse().unwrap()))
+        }
+        Some(c) if c.is_alphabetic() => {
+            let mut ident = String::new();
+            while let Some(d) = iter.next_if(|c| c.is_alphanumeric()) {
+                ident.push(d);
+            }
+            match ident.as_str() {
+                "old" => Ok(Expr::Old),
+                _ => Err(format!("Unknown variable {}", ident)),
+            }
+        }
+        c => Err(format!("Unexpected {:?}", c)),
+    }
+}
+
+// Precedence climbing: keep folding operators as long as they bind at least
+// as tightly as `min_precedence`
+fn parse_expr(iter: &mut Peekable<Chars>, min_precedence: u8) -> Result<Expr, String> {
+    let mut lhs = parse_atom(iter)?;
+    loop {
+        skip_whitespace(iter);
+        let op = match iter.peek() {
+            Some('+') => BinOp::Add,
+            Some('-') => BinOp::Sub,
+            Some('*') => BinOp::Mul,
+            Some('/') => BinOp::Div,
+            _ => return Ok(lhs),
+        };
+        if op.precedence() < min_precedence {
+            return Ok(lhs);
+        }
+        iter.next();
+        let rhs = parse_expr(iter, op.precedence() + 1)?;
+        lhs = Expr::BinOp(op, Box::new(lhs), Box::new(rhs));
+    }
+}
+
+impl FromStr for Expr {
+    type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "old" => Ok(Self::Old),
-            num => Ok(Self::Num(num.parse().unwrap())),
+        let s = s.trim();
+        let s = s.strip_prefix("Operation: new = ").unwrap_or(s);
+        let mut iter = s.chars().peekable();
+        let expr = parse_expr(&mut iter, 0)?;
+        skip_whitespace(&mut iter);
+        match iter.next() {
+            None => Ok(expr),
+            Some(c) => Err(format!("Trailing input at {:?}", c)),
+        }
+    }
+}
+
+impl Expr {
+    // Reducing by `modulo` after every operation keeps each operand below
+    // it, so no product ever exceeds its square
+    fn eval<W: Worry>(&self, old: &W, modulo: Option<&W>) -> W {
+        let value = match self {
+            Expr::Old => old.clone(),
+            Expr::Num(num) => W::from(*num),
+            Expr::BinOp(op, a, b) => op.apply(a.eval(old, modulo), b.eval(old, modulo)),
+        };
+        match modulo {
+            Some(m) => value % m.clone(),
+            None => value,
+        }
+    }
+
+    // Only sums and products give the same remainder when `old` is replaced
+    // by anything congruent to it
+    fn keeps_congruence(&self) -> bool {
+        match self {
+            Expr::Old | Expr::Num(_) => true,
+            Expr::BinOp(BinOp::Add | BinOp::Mul, a, b) => {
+                a.keeps_congruence() && b.keeps_congruence()
+            }
+            Expr::BinOp(BinOp::Sub | BinOp::Div, _, _) => false,
         }
     }
 }
 
-impl FromStr for Operation {
-    type Err = ();
+impl FromStr for Relief {
+    type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let op = s.trim().strip_prefix("Operation: new = ").unwrap();
-        match op.splitn(3, ' ').collect::<Vec<&str>>()[..] {
-            [val1, "+", val2] => Ok(Self::Add {
-                first: Value::from_str(val1).unwrap(),
-                second: Value::from_str(val2).unwrap(),
-            }),
-            [val1, "*", val2] => Ok(Self::Multiply {
-                first: Value::from_str(val1).unwrap(),
-                second: Value::from_str(val2).unwrap(),
-            }),
-            _ => unreachable!(),
+        match s {
+            "none" => Ok(Self::None),
+            "lcm" => Ok(Self::ModuloLcm),
+            num => num
+                .parse()
+                .map(Self::DivideBy)
+                .map_err(|_| format!("Invalid relief {}", num)),
         }
     }
 }
@@ -68,7 +231,7 @@ where
     s.trim().strip_prefix(prefix).unwrap().parse().unwrap()
 }
 
-impl From<&[String]> for Monkey {
+impl<W: Worry> From<&[String]> for Monkey<W> {
     fn from(s: &[String]) -> Self {
         Self {
             items: s[1]
@@ -76,52 +239,67 @@ impl From<&[String]> for Monkey {
                 .strip_prefix("Starting items: ")
                 .unwrap()
                 .split(',')
-                .map(|s| s.trim().parse().unwrap())
+                .map(|s| W::from(s.trim().parse::<u64>().unwrap()))
                 .collect(),
             test: Test {
                 divisible: get_and_parse(&s[3], "Test: divisible by "),
                 monkey_true: get_and_parse(&s[4], "If true: throw to monkey "),
                 monkey_false: get_and_parse(&s[5], "If false: throw to monkey "),
             },
-            operation: Operation::from_str(&s[2]).unwrap(),
+            operation: Expr::from_str(&s[2]).unwrap(),
             inspected: 0,
         }
     }
 }
 
-impl Value {
-    fn get(&self, item: u64) -> u64 {
-        match self {
-            Value::Old => item,
-            Value::Num(num) => *num,
-        }
-    }
-}
-
-impl Monkey {
-    fn run_test(&self, item: u64) -> usize {
-        if item % self.test.divisible == 0 {
+impl<W: Worry> Monkey<W> {
+    fn run_test(&self, item: &W) -> usize {
+        if item.is_divisible(self.test.divisible) {
             self.test.monkey_true
         } else {
             self.test.monkey_false
         }
     }
 
-    fn inspect_item(&mut self, item: u64, task: &Task, divisor: u64) -> u64 {
+    fn inspect_item(&mut self, item: &W, relief: Relief, lcm: Option<&W>) -> W {
         self.inspected += 1;
-        let new_worry_level = match &self.operation {
-            Operation::Multiply { first, second } => first.get(item) * second.get(item),
-            Operation::Add { first, second } => first.get(item) + second.get(item),
-        };
-        if task == &Task::One {
-            new_worry_level / 3
-        } else {
-            new_worry_level % divisor
+        let new_worry_level = self.operation.eval(item, lcm);
+        match relief {
+            Relief::DivideBy(k) => new_worry_level.divide_down(k),
+            Relief::ModuloLcm | Relief::None => new_worry_level,
+        }
+    }
+}
+
+impl std::fmt::Display for RoundStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (monkey, throws) in self.throws.iter().enumerate() {
+            let targets = throws
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(|(target, count)| format!("{}x monkey {}", count, target))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                f,
+                "Monkey {}: held {}, inspected {}, threw [{}]",
+                monkey, self.held[monkey], self.inspections[monkey], targets
+            )?;
         }
+        Ok(())
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
     }
 }
 
-impl Monkeys {
+impl<W: Worry> Monkeys<W> {
     fn new(input: &[String]) -> Self {
         Self {
             monkeys: input
@@ -131,29 +309,42 @@ impl Monkeys {
         }
     }
 
-    fn get_divisor(&self) -> u64 {
-        self.monkeys.iter().fold(0, |acc, monkey| {
-            if acc == 0 {
-                monkey.test.divisible
-            } else {
-                monkey.test.divisible * acc
+    fn play_round(&mut self, relief: Relief, lcm: Option<&W>) -> RoundStats {
+        let n = self.monkeys.len();
+        let mut stats = RoundStats {
+            held: self.monkeys.iter().map(|m| m.items.len()).collect(),
+            inspections: vec![0; n],
+            throws: vec![vec![0; n]; n],
+        };
+        for monkey in 0..n {
+            while let Some(item) = self.monkeys[monkey].items.pop_front() {
+                let m = &mut self.monkeys[monkey];
+                let worry = m.inspect_item(&item, relief, lcm);
+                let new = m.run_test(&worry);
+                stats.inspections[monkey] += 1;
+                stats.throws[monkey][new] += 1;
+                self.monkeys[new].items.push_back(worry);
             }
-        })
+        }
+        stats
     }
 
-    fn play_rounds(&mut self, num_rounds: i32, task: &Task) {
-        let divisor = self.get_divisor();
-        for _ in 0..num_rounds {
-            for monkey in 0..self.monkeys.len() {
-                for i in 0..self.monkeys.get(monkey).unwrap().items.len() {
-                    let m = self.monkeys.get_mut(monkey).unwrap();
-                    let worry = m.inspect_item(*m.items.get(i).unwrap(), task, divisor);
-                    let new = m.run_test(worry);
-                    self.monkeys.get_mut(new).unwrap().items.push_back(worry);
-                }
-                self.monkeys.get_mut(monkey).unwrap().items.clear();
+    fn play_rounds(&mut self, num_rounds: i32, relief: Relief, lcm: Option<&W>) -> Vec<RoundStats> {
+        if let Relief::ModuloLcm = relief {
+            if let Some(monkey) = self
+                .monkeys
+                .iter()
+                .position(|m| !m.operation.keeps_congruence())
+            {
+                panic!(
+                    "Monkey {} subtracts or divides, which the lcm relief cannot reduce",
+                    monkey
+                );
             }
         }
+        (0..num_rounds)
+            .map(|_| self.play_round(relief, lcm))
+            .collect()
     }
 
     fn get_monkeybusiness(&self) -> u64 {
@@ -168,22 +359,68 @@ impl Monkeys {
     }
 }
 
-fn task_one(input: &[String]) -> u64 {
-    let mut monkeys = Monkeys::new(input);
-    monkeys.play_rounds(20, &Task::One);
-    monkeys.get_monkeybusiness()
+// The lcm of every divisor, which can outgrow a u64 long before any one
+// divisor does
+fn get_lcm(input: &[String]) -> BigUint {
+    input
+        .split(|s| s.is_empty())
+        .map(|s| get_and_parse::<u64>(&s[3], "Test: divisible by "))
+        .fold(BigUint::from(1u64), |acc, divisible| {
+            let rem = u64::try_from(&acc % divisible).unwrap();
+            acc / gcd(divisible, rem) * divisible
+        })
 }
 
-fn task_two(input: &[String]) -> u64 {
-    let mut monkeys = Monkeys::new(input);
-    monkeys.play_rounds(10000, &Task::Two);
+fn simulate<W: Worry>(input: &[String], num_rounds: i32, relief: Relief, lcm: Option<W>) -> u64 {
+    let mut monkeys = Monkeys::<W>::new(input);
+    let stats = monkeys.play_rounds(num_rounds, relief, lcm.as_ref());
+    if show_stats() {
+        stats
+            .iter()
+            .enumerate()
+            .for_each(|(round, stats)| println!("== Round {} ==\n{}", round + 1, stats));
+    }
     monkeys.get_monkeybusiness()
 }
 
+fn monkeybusiness(input: &[String], num_rounds: i32, relief: Relief) -> u64 {
+    match relief {
+        // Worry below an lcm that fits a u64 squares to fit a u128
+        Relief::ModuloLcm => match u64::try_from(get_lcm(input)) {
+            Ok(lcm) => simulate(input, num_rounds, relief, Some(lcm as u128)),
+            Err(e) => simulate(
+                input,
+                num_rounds,
+                relief,
+                Some(BigInt::from(e.into_original())),
+            ),
+        },
+        // Only the modulo keeps the worry levels bounded, so anything else
+        // needs arbitrary precision. Signed, as subtracting can go below zero
+        Relief::DivideBy(_) | Relief::None => simulate::<BigInt>(input, num_rounds, relief, None),
+    }
+}
+
+fn task_one(input: &[String]) -> Answer {
+    monkeybusiness(input, 20, get_relief().unwrap_or(Relief::DivideBy(3))).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    monkeybusiness(input, 10000, Relief::ModuloLcm).into()
+}
+
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -197,27 +434,21 @@ where
         .collect()
 }
 
-#[derive(PartialEq, Eq)]
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -227,3 +458,11 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn get_relief() -> Option<Relief> {
+    std::env::var("RELIEF").ok().map(|r| r.parse().unwrap())
+}
+
+fn show_stats() -> bool {
+    std::env::var("STATS").is_ok()
+}
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A heightmap at least 30 squares on a side, rising towards the best signal
+/// with some dips on the way. Maps where `S` can't reach `E` are redrawn
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let (width, height) = (size.max(30) as i64, (size / 2).max(30) as i64);
+    loop {
+        let end = (rng.range(0..width), rng.range(0..height));
+        let distance = |(x, y): (i64, i64)| (x - end.0).abs() + (y - end.1).abs();
+        let far = [
+            (0, 0),
+            (width - 1, 0),
+            (0, height - 1),
+            (width - 1, height - 1),
+        ]
+        .into_iter()
+        .max_by_key(|p| distance(*p))
+        .unwrap();
+        let start = (rng.range(0..width), rng.range(0..height));
+        let start = if distance(start) < distance(far) / 2 {
+            far
+        } else {
+            start
+        };
+
+        let lines: Vec<String> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        if (x, y) == end {
+                            'E'
+                        } else if (x, y) == start {
+                            'S'
+                        } else {
+                            let climb = 25 - 26 * distance((x, y)) / distance(far).max(26);
+                            let dip = rng.chance(0.3) as i64 * rng.range(1..4);
+                            (b'a' + (climb - dip).max(0) as u8) as char
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        if steps_to_end(&lines)
+            .iter()
+            .flatten()
+            .any(|(c, d)| *c == 'S' && d.is_some())
+        {
+            return lines;
+        }
+    }
+}
+
+fn elevation(c: char) -> i64 {
+    match c {
+        'S' => 0,
+        'E' => 25,
+        c => c as i64 - 'a' as i64,
+    }
+}
+
+// Walks back from `E`, so every square learns its distance in one search
+fn steps_to_end(input: &[String]) -> Vec<Vec<(char, Option<usize>)>> {
+    let grid: Vec<Vec<char>> = input.iter().map(|l| l.chars().collect()).collect();
+    let mut steps: Vec<Vec<(char, Option<usize>)>> = grid
+        .iter()
+        .map(|row| row.iter().map(|c| (*c, None)).collect())
+        .collect();
+    let mut queue = VecDeque::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, c) in row.iter().enumerate() {
+            if *c == 'E' {
+                steps[y][x].1 = Some(0);
+                queue.push_back((x, y));
+            }
+        }
+    }
+    while let Some((x, y)) = queue.pop_front() {
+        let here = steps[y][x].1.unwrap();
+        let neighbours = [
+            (x.wrapping_sub(1), y),
+            (x + 1, y),
+            (x, y.wrapping_sub(1)),
+            (x, y + 1),
+        ];
+        for (nx, ny) in neighbours {
+            let Some(c) = grid.get(ny).and_then(|row| row.get(nx)) else {
+                continue;
+            };
+            if steps[ny][nx].1.is_none() && elevation(grid[y][x]) <= elevation(*c) + 1 {
+                steps[ny][nx].1 = Some(here + 1);
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    steps
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let steps = steps_to_end(input);
+    let from = |starts: &[char]| {
+        steps
+            .iter()
+            .flatten()
+            .filter(|(c, _)| starts.contains(c))
+            .filter_map(|(_, d)| *d)
+            .min()
+            .unwrap()
+    };
+    (from(&['S']).into(), from(&['S', 'a']).into())
+}
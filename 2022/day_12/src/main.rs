@@ -1,5 +1,10 @@
 use std::{cmp::Ordering, collections::BinaryHeap};
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 struct State {
     cost: i32,
@@ -127,16 +132,16 @@ fn get_pos(vec: &Vec<Position>, c: char) -> [i32; 2] {
     vec.iter().find(|s| s.character == c).unwrap().position
 }
 
-fn task_one(input: &[String]) -> i32 {
+fn task_one(input: &[String]) -> Answer {
     let matrix: Matrix<Position> = input.into();
 
     let start_pos = get_pos(&matrix.vec, 'S');
     let end_pos = get_pos(&matrix.vec, 'E');
 
-    shortest_path(&matrix, start_pos, end_pos).unwrap()
+    shortest_path(&matrix, start_pos, end_pos).unwrap().into()
 }
 
-fn task_two(input: &[String]) -> i32 {
+fn task_two(input: &[String]) -> Answer {
     let matrix: Matrix<Position> = input.into();
 
     let start = matrix
@@ -157,13 +162,21 @@ fn task_two(input: &[String]) -> i32 {
 
     paths.sort_unstable();
 
-    paths[0]
+    paths[0].into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -177,26 +190,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
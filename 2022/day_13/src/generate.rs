@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+#[derive(Clone)]
+enum Packet {
+    List(Vec<Packet>),
+    Number(i64),
+}
+
+impl Packet {
+    fn random(rng: &mut Rng, depth: usize) -> Self {
+        let len = if depth > 3 {
+            0
+        } else {
+            rng.range(0..5) as usize
+        };
+        Packet::List(
+            (0..len)
+                .map(|_| {
+                    if rng.chance(0.3) {
+                        Packet::random(rng, depth + 1)
+                    } else {
+                        Packet::Number(rng.range(0..11))
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    // Changes one spot of the packet, so the pair is decided somewhere deep
+    // inside rather than at the first element
+    fn mutate(&mut self, rng: &mut Rng) {
+        match self {
+            Packet::Number(n) => match rng.below(3) {
+                0 => *n = rng.range(0..11),
+                1 => *self = Packet::List(vec![Packet::Number(*n)]),
+                _ => *self = Packet::List(vec![]),
+            },
+            Packet::List(items) if items.is_empty() || rng.chance(0.2) => match rng.below(2) {
+                0 if !items.is_empty() => {
+                    items.pop();
+                }
+                _ => items.push(Packet::Number(rng.range(0..11))),
+            },
+            Packet::List(items) => {
+                let i = rng.below(items.len());
+                items[i].mutate(rng);
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Packet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Packet::Number(n) => write!(f, "{}", n),
+            Packet::List(items) => {
+                let items: Vec<String> = items.iter().map(Packet::to_string).collect();
+                write!(f, "[{}]", items.join(","))
+            }
+        }
+    }
+}
+
+/// `size` pairs of packets. Most right packets are the left one changed in a
+/// single spot, and no packet repeats a divider packet
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for pair in 0..size.max(1) {
+        if pair > 0 {
+            lines.push(String::new());
+        }
+        let left = Packet::random(rng, 0);
+        let mut right = left.clone();
+        if rng.chance(0.8) {
+            right.mutate(rng);
+        } else {
+            right = Packet::random(rng, 0);
+        }
+        for packet in [left, right] {
+            let packet = packet.to_string();
+            let divider = packet == "[[2]]" || packet == "[[6]]";
+            lines.push(if divider { "[]".to_string() } else { packet });
+        }
+    }
+    lines
+}
+
+#[derive(Debug)]
+enum Token {
+    Open,
+    Close,
+    Number(i64),
+}
+
+fn tokens(packet: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = packet.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '[' => tokens.push(Token::Open),
+            ']' => tokens.push(Token::Close),
+            ',' => {}
+            d => {
+                let mut n = d.to_digit(10).unwrap() as i64;
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    n = n * 10 + d.to_digit(10).unwrap() as i64;
+                }
+                tokens.push(Token::Number(n));
+            }
+        }
+    }
+    tokens
+}
+
+// Compares token streams directly. A number against a list is replaced by
+// the tokens of a list holding just that number
+fn compare(left: &str, right: &str) -> Ordering {
+    let (mut left, mut right) = (tokens(left), tokens(right));
+    left.reverse();
+    right.reverse();
+    loop {
+        match (left.pop(), right.pop()) {
+            (None, None) => return Ordering::Equal,
+            (Some(Token::Open), Some(Token::Open)) | (Some(Token::Close), Some(Token::Close)) => {}
+            (Some(Token::Close), _) => return Ordering::Less,
+            (_, Some(Token::Close)) => return Ordering::Greater,
+            (Some(Token::Number(a)), Some(Token::Number(b))) if a != b => return a.cmp(&b),
+            (Some(Token::Number(_)), Some(Token::Number(_))) => {}
+            (Some(Token::Number(a)), Some(Token::Open)) => {
+                left.extend([Token::Close, Token::Number(a)])
+            }
+            (Some(Token::Open), Some(Token::Number(b))) => {
+                right.extend([Token::Close, Token::Number(b)])
+            }
+            (l, r) => unreachable!("{:?} {:?}", l, r),
+        }
+    }
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let ordered: usize = input
+        .split(|l| l.is_empty())
+        .enumerate()
+        .filter(|(_, pair)| compare(&pair[0], &pair[1]) == Ordering::Less)
+        .map(|(i, _)| i + 1)
+        .sum();
+    let packets: Vec<&String> = input.iter().filter(|l| !l.is_empty()).collect();
+    let before = |divider: &str| {
+        packets
+            .iter()
+            .filter(|p| compare(p, divider) == Ordering::Less)
+            .count()
+    };
+    let key = (before("[[2]]") + 1) * (before("[[6]]") + 2);
+    (ordered.into(), key.into())
+}
@@ -1,135 +1,201 @@
-use std::{cmp::Ordering, iter::Peekable, slice::Iter};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt, slice, str::FromStr};
 
-#[derive(Debug, Eq, PartialEq, Clone)]
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
 enum Item {
     List(Vec<Item>),
-    Number(i32),
+    Number(i64),
 }
 
-impl Ord for Item {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match (self, other) {
-            (Item::List(a), Item::Number(b)) => a.cmp(&vec![Item::Number(*b)]),
-            (Item::Number(a), Item::List(b)) => vec![Item::Number(*a)].cmp(b),
-            (Item::Number(a), Item::Number(b)) => a.cmp(b),
-            (Item::List(a), Item::List(b)) => a.cmp(b),
-        }
-    }
+#[derive(Debug)]
+enum Reason {
+    Number(i64, i64),
+    LeftRanOut,
+    RightRanOut,
 }
 
-impl PartialOrd for Item {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+#[derive(Debug)]
+struct Difference {
+    ordering: Ordering,
+    path: Vec<usize>,
+    reason: Reason,
 }
 
-fn parse_number(iter: &mut Peekable<Iter<char>>, c: &char) -> Item {
-    let mut vec = vec![c];
-    while let Some(d) = iter.peek() {
-        match d {
-            ',' => {
-                iter.next();
-                break;
+impl Item {
+    fn as_slice(&self) -> &[Item] {
+        match self {
+            Item::List(list) => list,
+            Item::Number(_) => slice::from_ref(self),
+        }
+    }
+
+    // Walks both packets in lockstep and stops at the first element that
+    // decides the ordering. The path is built in reverse while unwinding
+    fn find_difference(&self, other: &Self, path: &mut Vec<usize>) -> Option<(Ordering, Reason)> {
+        if let (Item::Number(a), Item::Number(b)) = (self, other) {
+            return match a.cmp(b) {
+                Ordering::Equal => None,
+                ordering => Some((ordering, Reason::Number(*a, *b))),
+            };
+        }
+
+        let (left, right) = (self.as_slice(), other.as_slice());
+        for (idx, (a, b)) in left.iter().zip(right).enumerate() {
+            if let Some(diff) = a.find_difference(b, path) {
+                path.push(idx);
+                return Some(diff);
             }
-            '[' | ']' => break,
-            num => {
-                vec.push(num);
-                iter.next();
+        }
+
+        match left.len().cmp(&right.len()) {
+            Ordering::Equal => None,
+            Ordering::Less => {
+                path.push(left.len());
+                Some((Ordering::Less, Reason::LeftRanOut))
+            }
+            Ordering::Greater => {
+                path.push(right.len());
+                Some((Ordering::Greater, Reason::RightRanOut))
             }
         }
     }
-    let num = vec.into_iter().collect::<String>().parse::<i32>().unwrap();
-    Item::Number(num)
-}
-
-fn parse_list(iter: &mut Peekable<Iter<char>>) -> Vec<Item> {
-    let mut vec = Vec::new();
-    while let Some(c) = iter.next() {
-        match c {
-            '[' => vec.push(Item::List(parse_list(iter))),
-            ']' => return vec,
-            ',' => continue,
-            num => vec.push(parse_number(iter, num)),
-        }
+
+    fn explain(&self, other: &Self) -> Option<Difference> {
+        let mut path = Vec::new();
+        self.find_difference(other, &mut path)
+            .map(|(ordering, reason)| {
+                path.reverse();
+                Difference {
+                    ordering,
+                    path,
+                    reason,
+                }
+            })
     }
-    vec
 }
 
-fn parse_packet(s: &str) -> Vec<Item> {
-    let chars: Vec<char> = s
-        .trim()
-        .strip_prefix('[')
-        .unwrap()
-        .strip_suffix(']')
-        .unwrap()
-        .chars()
-        .collect();
+impl Ord for Item {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.explain(other)
+            .map_or(Ordering::Equal, |diff| diff.ordering)
+    }
+}
 
-    let mut v = Vec::new();
-    let mut iter = chars.iter().peekable();
+impl PartialOrd for Item {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-    while let Some(c) = iter.next() {
-        match c {
-            ',' => continue,
-            '[' => v.push(Item::List(parse_list(&mut iter))),
-            num => v.push(parse_number(&mut iter, num)),
-        }
+impl FromStr for Item {
+    type Err = serde_json::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s.trim())
     }
+}
 
-    v
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Item::Number(num) => write!(f, "{}", num),
+            Item::List(list) => {
+                write!(f, "[")?;
+                for (idx, item) in list.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
 }
 
-fn task_one(input: &[String]) -> usize {
-    let v: Vec<(String, String)> = input
-        .split(|s| s.is_empty())
-        .map(|v| (v[0].clone(), v[1].clone()))
-        .collect();
-
-    let mut correct = 0;
-    v.into_iter().enumerate().for_each(|(idx, (left, right))| {
-        let left = parse_packet(&left);
-        let right = parse_packet(&right);
-
-        match left.cmp(&right) {
-            Ordering::Less => correct += idx + 1,
-            Ordering::Greater => {}
-            Ordering::Equal => {}
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match self.ordering {
+            Ordering::Less => "right order",
+            _ => "wrong order",
+        };
+        match self.reason {
+            Reason::Number(a, b) => write!(f, "{}: {} vs {} at {:?}", verdict, a, b, self.path),
+            Reason::LeftRanOut => write!(f, "{}: left ran out at {:?}", verdict, self.path),
+            Reason::RightRanOut => write!(f, "{}: right ran out at {:?}", verdict, self.path),
         }
-    });
-    correct
+    }
+}
+
+fn parse_packet(s: &str) -> Item {
+    s.parse().unwrap()
 }
 
-fn task_two(input: &[String]) -> usize {
-    let v: Vec<(String, String)> = input
+fn task_one(input: &[String]) -> Answer {
+    input
         .split(|s| s.is_empty())
-        .map(|v| (v[0].clone(), v[1].clone()))
-        .collect();
+        .map(|v| (parse_packet(&v[0]), parse_packet(&v[1])))
+        .enumerate()
+        .filter_map(|(idx, (left, right))| {
+            let diff = left.explain(&right);
+            if explain() {
+                match &diff {
+                    Some(diff) => println!("Pair {}: {}", idx + 1, diff),
+                    None => println!("Pair {}: equal", idx + 1),
+                }
+            }
+            match diff {
+                Some(diff) if diff.ordering == Ordering::Less => Some(idx + 1),
+                _ => None,
+            }
+        })
+        .sum::<usize>()
+        .into()
+}
 
+fn task_two(input: &[String]) -> Answer {
     let divider_1 = parse_packet("[[2]]");
     let divider_2 = parse_packet("[[6]]");
 
     let mut vec = vec![divider_1.clone(), divider_2.clone()];
-
-    v.into_iter().for_each(|(left, right)| {
-        vec.push(parse_packet(&left));
-        vec.push(parse_packet(&right));
-    });
+    vec.extend(
+        input
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| parse_packet(s)),
+    );
 
     vec.sort();
 
-    vec.into_iter().enumerate().fold(1, |acc, (idx, item)| {
-        if item == divider_1 || item == divider_2 {
-            acc * (idx + 1)
-        } else {
-            acc
-        }
-    })
+    vec.into_iter()
+        .enumerate()
+        .fold(1, |acc, (idx, item)| {
+            if item == divider_1 || item == divider_2 {
+                acc * (idx + 1)
+            } else {
+                acc
+            }
+        })
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -143,26 +209,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -172,3 +233,7 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn explain() -> bool {
+    std::env::var("EXPLAIN").is_ok()
+}
@@ -0,0 +1,89 @@
+use std::collections::HashSet;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` paths of rock below the sand source, each a few straight segments.
+/// As in the puzzle, sand always ends up falling past the rock before it
+/// piles up to the source
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    loop {
+        let paths = paths(rng, size);
+        if !pour(&paths, false).1 {
+            return paths;
+        }
+    }
+}
+
+fn paths(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size.max(1))
+        .map(|_| {
+            let (mut x, mut y) = (rng.range(470..531), rng.range(2..40));
+            let mut points = vec![format!("{},{}", x, y)];
+            for segment in 0..rng.range(1..6) {
+                if segment % 2 == 0 {
+                    x += rng.range(-6..7);
+                } else {
+                    y = (y + rng.range(-6..7)).max(1);
+                }
+                points.push(format!("{},{}", x, y));
+            }
+            points.join(" -> ")
+        })
+        .collect()
+}
+
+// Drops one unit at a time from the source, and tells whether it stopped
+// because the source got blocked. Without a floor a unit that passes the
+// lowest rock falls forever
+fn pour(input: &[String], floor: bool) -> (usize, bool) {
+    let mut blocked = HashSet::new();
+    for line in input {
+        let points: Vec<(i64, i64)> = line
+            .split(" -> ")
+            .map(|p| {
+                let (x, y) = p.split_once(',').unwrap();
+                (x.parse().unwrap(), y.parse().unwrap())
+            })
+            .collect();
+        for w in points.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            for x in a.0.min(b.0)..=a.0.max(b.0) {
+                for y in a.1.min(b.1)..=a.1.max(b.1) {
+                    blocked.insert((x, y));
+                }
+            }
+        }
+    }
+    let lowest = blocked.iter().map(|p| p.1).max().unwrap();
+
+    let mut units = 0;
+    while !blocked.contains(&(500, 0)) {
+        let (mut x, mut y) = (500, 0);
+        loop {
+            if y == lowest + 1 {
+                break;
+            }
+            match [x, x - 1, x + 1]
+                .into_iter()
+                .find(|nx| !blocked.contains(&(*nx, y + 1)))
+            {
+                Some(nx) => {
+                    x = nx;
+                    y += 1;
+                }
+                None => break,
+            }
+        }
+        if y == lowest + 1 && !floor {
+            return (units, false);
+        }
+        blocked.insert((x, y));
+        units += 1;
+    }
+    (units, true)
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    (pour(input, false).0.into(), pour(input, true).0.into())
+}
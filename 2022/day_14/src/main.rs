@@ -2,6 +2,11 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 fn parse(input: &[String]) -> HashSet<(i32, i32)> {
     let mut set: HashSet<(i32, i32)> = HashSet::new();
     input
@@ -68,7 +73,7 @@ fn let_it_rain(
     }
 }
 
-fn task_one(input: &[String]) -> usize {
+fn task_one(input: &[String]) -> Answer {
     let mut set = parse(input);
     let max_y = set.iter().max_by_key(|a| a.1).unwrap().1;
 
@@ -76,10 +81,10 @@ fn task_one(input: &[String]) -> usize {
     while let_it_rain(&mut set, (500, 0), max_y, Task::One).is_some() {
         num += 1;
     }
-    num
+    num.into()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     let mut set = parse(input);
     let max_y = set.iter().max_by_key(|a| a.1).unwrap().1 + 2;
 
@@ -87,13 +92,21 @@ fn task_two(input: &[String]) -> usize {
     while let_it_rain(&mut set, (500, 0), max_y, Task::Two).is_some() {
         num += 1;
     }
-    num
+    num.into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -107,27 +120,21 @@ where
         .collect()
 }
 
-#[derive(PartialEq, Eq)]
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
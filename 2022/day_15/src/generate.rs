@@ -0,0 +1,112 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const ROW: i64 = 2_000_000;
+const MAX: i64 = 4_000_000;
+
+/// Eight large sensors that leave exactly one position of the search area
+/// out of reach, plus `size` smaller ones that don't reach it either
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let gap = (rng.range(0..MAX + 1), rng.range(0..MAX + 1));
+    let distance = |(x, y): (i64, i64)| (x - gap.0).abs() + (y - gap.1).abs();
+
+    let mut sensors = Vec::new();
+    // Each sensor on an axis covers the cone on its side, and each one on a
+    // diagonal covers the points where two cones meet
+    let far = rng.range(3 * MAX..4 * MAX);
+    for (dx, dy) in [
+        (1, 0),
+        (-1, 0),
+        (0, 1),
+        (0, -1),
+        (1, 1),
+        (1, -1),
+        (-1, 1),
+        (-1, -1),
+    ] {
+        let sensor = (gap.0 + dx * far, gap.1 + dy * far);
+        sensors.push((sensor, distance(sensor) - 1));
+    }
+    for _ in 0..size {
+        let sensor = (
+            rng.range(-MAX / 4..MAX * 5 / 4),
+            rng.range(-MAX / 4..MAX * 5 / 4),
+        );
+        sensors.push((sensor, rng.range(0..distance(sensor))));
+    }
+    rng.shuffle(&mut sensors);
+
+    sensors
+        .into_iter()
+        .map(|((x, y), radius)| {
+            let dx = rng.range(-radius..radius + 1);
+            let dy = (radius - dx.abs()) * if rng.chance(0.5) { 1 } else { -1 };
+            format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+                x,
+                y,
+                x + dx,
+                y + dy
+            )
+        })
+        .collect()
+}
+
+fn numbers(line: &str) -> Vec<i64> {
+    line.split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+// Scans the search area a row at a time, skipping past the end of whichever
+// sensor covers the current position
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let readings: Vec<Vec<i64>> = input.iter().map(|l| numbers(l)).collect();
+    let sensors: Vec<(i64, i64, i64)> = readings
+        .iter()
+        .map(|r| (r[0], r[1], (r[0] - r[2]).abs() + (r[1] - r[3]).abs()))
+        .collect();
+    let span = |y: i64, (sx, sy, radius): (i64, i64, i64)| {
+        let reach = radius - (y - sy).abs();
+        (reach >= 0).then_some((sx - reach, sx + reach))
+    };
+
+    let mut spans: Vec<(i64, i64)> = sensors.iter().filter_map(|s| span(ROW, *s)).collect();
+    spans.sort_unstable();
+    let (mut covered, mut end) = (0, i64::MIN);
+    for (a, b) in spans {
+        if b > end {
+            covered += b - a.max(end + 1) + 1;
+            end = b;
+        }
+    }
+    let mut beacons: Vec<i64> = readings
+        .iter()
+        .filter(|r| r[3] == ROW)
+        .map(|r| r[2])
+        .collect();
+    beacons.sort_unstable();
+    beacons.dedup();
+
+    let mut frequency = None;
+    'rows: for y in 0..=MAX {
+        let mut x = 0;
+        while x <= MAX {
+            match sensors
+                .iter()
+                .filter_map(|s| span(y, *s))
+                .find(|(a, b)| (*a..=*b).contains(&x))
+            {
+                Some((_, b)) => x = b + 1,
+                None => {
+                    frequency = Some(x * MAX + y);
+                    break 'rows;
+                }
+            }
+        }
+    }
+    (
+        (covered - beacons.len() as i64).into(),
+        frequency.unwrap().into(),
+    )
+}
@@ -2,122 +2,299 @@ use std::{collections::HashSet, str::FromStr};
 
 use itertools::Itertools;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+// Rows and columns are handled in rotated coordinates where every diamond
+// edge lies on either `u = x + y` or `v = x - y` being constant
 #[derive(Debug)]
 struct Sensor {
-    pos: (i32, i32),
-    beacon: (i32, i32),
+    pos: (i64, i64),
+    beacon: (i64, i64),
+    radius: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bounds {
+    min: (i64, i64),
+    max: (i64, i64),
 }
 
 trait Distance {
-    fn distance(&self, other: (i32, i32)) -> i32;
+    fn distance(&self, other: (i64, i64)) -> i64;
 }
 
-impl Distance for (i32, i32) {
-    fn distance(&self, other: (i32, i32)) -> i32 {
+impl Distance for (i64, i64) {
+    fn distance(&self, other: (i64, i64)) -> i64 {
         (self.0 - other.0).abs() + (self.1 - other.1).abs()
     }
 }
 
+fn parse_point(s: &str) -> (i64, i64) {
+    let (x, y) = s.split(',').collect_tuple().unwrap();
+    let x = x.split('=').find_map(|s| s.parse::<i64>().ok()).unwrap();
+    let y = y.split('=').find_map(|s| s.parse::<i64>().ok()).unwrap();
+    (x, y)
+}
+
 impl FromStr for Sensor {
     type Err = ();
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (sensor, beacon) = s.split(':').collect_tuple().unwrap();
+        let pos = parse_point(sensor);
+        let beacon = parse_point(beacon);
 
-        let (x, y) = sensor.split(',').collect_tuple().unwrap();
-        let x = x.split('=').find_map(|s| s.parse::<i32>().ok()).unwrap();
-        let y = y.split('=').find_map(|s| s.parse::<i32>().ok()).unwrap();
+        Ok(Self {
+            pos,
+            beacon,
+            radius: pos.distance(beacon),
+        })
+    }
+}
 
-        let sensor = (x, y);
+impl FromStr for Bounds {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (x0, y0, x1, y1) = s
+            .split(',')
+            .map(|n| n.parse().unwrap())
+            .collect_tuple()
+            .ok_or(())?;
+        Ok(Self {
+            min: (x0, y0),
+            max: (x1, y1),
+        })
+    }
+}
 
-        let (x, y) = beacon.split(',').collect_tuple().unwrap();
-        let x = x.split('=').find_map(|s| s.parse::<i32>().ok()).unwrap();
-        let y = y.split('=').find_map(|s| s.parse::<i32>().ok()).unwrap();
+impl Sensor {
+    fn covers(&self, point: (i64, i64)) -> bool {
+        self.pos.distance(point) <= self.radius
+    }
 
-        let beacon = (x, y);
+    fn row_span(&self, y: i64) -> Option<(i64, i64)> {
+        let diff = self.radius - (y - self.pos.1).abs();
+        (diff >= 0).then_some((self.pos.0 - diff, self.pos.0 + diff))
+    }
 
-        Ok(Self {
-            pos: sensor,
-            beacon,
-        })
+    fn u(&self) -> i64 {
+        self.pos.0 + self.pos.1
+    }
+
+    fn v(&self) -> i64 {
+        self.pos.0 - self.pos.1
     }
 }
 
-fn task_one(input: &[String]) -> i32 {
-    let line = 2000000;
-    let mut beacon_set = HashSet::new();
-    let mut vec: Vec<Range<i32>> = Vec::new();
-    input.iter().for_each(|s| {
-        let sensor = Sensor::from_str(s).unwrap();
-        beacon_set.insert(sensor.beacon);
-        let diff = sensor.pos.distance(sensor.beacon) - (line - sensor.pos.1).abs();
-        let mut list = [sensor.pos.0 - diff, sensor.pos.0 + diff];
-        list.sort();
-
-        vec.push(list[0]..list[1] + 1);
-    });
-
-    vec.sort_by(|left, right| left.start.cmp(&right.start));
-    let merged: Vec<_> = merge_ranges(vec).collect();
-    let mut num_beacons = 0;
-    beacon_set.iter().for_each(|point| {
-        if line == point.1 && merged[0].contains(&point.0) {
-            num_beacons += 1;
-        }
-    });
+impl Bounds {
+    fn contains(&self, (x, y): (i64, i64)) -> bool {
+        (self.min.0..=self.max.0).contains(&x) && (self.min.1..=self.max.1).contains(&y)
+    }
 
-    merged[0].end - merged[0].start - num_beacons
+    fn width(&self) -> i64 {
+        self.max.0 - self.min.0 + 1
+    }
+
+    fn area(&self) -> i64 {
+        self.width() * (self.max.1 - self.min.1 + 1)
+    }
 }
 
-fn task_two(input: &[String]) -> i64 {
-    let max = 4000000;
-    for line in 0..=max {
-        let mut vec: Vec<Range<i32>> = Vec::new();
-        let mut beacon_set = HashSet::new();
-        for s in input.iter() {
-            let sensor = Sensor::from_str(s).unwrap();
-            beacon_set.insert(sensor.beacon);
-            let distance = sensor.pos.distance(sensor.beacon);
-
-            let diff = distance - (line - sensor.pos.1).abs();
-            if diff < 0 {
-                continue;
-            }
+fn parse_sensors(input: &[String]) -> Vec<Sensor> {
+    input.iter().map(|s| Sensor::from_str(s).unwrap()).collect()
+}
 
-            let mut list = [sensor.pos.0 - diff, sensor.pos.0 + diff];
-            list.sort();
-            let mut start = list[0];
-            let mut end = list[1];
+// Merged and clipped covered intervals on a single row
+fn row_intervals(sensors: &[Sensor], y: i64, bounds: &Bounds) -> Vec<(i64, i64)> {
+    let mut spans: Vec<_> = sensors
+        .iter()
+        .filter_map(|sensor| sensor.row_span(y))
+        .map(|(start, end)| (start.max(bounds.min.0), end.min(bounds.max.0)))
+        .filter(|(start, end)| start <= end)
+        .collect();
+    spans.sort_unstable();
+
+    let mut merged: Vec<(i64, i64)> = Vec::new();
+    for (start, end) in spans {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
 
-            if start < 0 && end < 0 {
-                break;
-            } else if start < 0 {
-                start = 0;
+fn row_covered(sensors: &[Sensor], y: i64, bounds: &Bounds) -> i64 {
+    row_intervals(sensors, y, bounds)
+        .iter()
+        .map(|(start, end)| end - start + 1)
+        .sum()
+}
+
+// Rows where the arrangement of interval endpoints can change. Every endpoint
+// follows a `u` or `v` line, so between two consecutive rows from here the
+// covered length of a row is linear in y
+fn breakpoints(sensors: &[Sensor], bounds: &Bounds) -> Vec<i64> {
+    let u_lines: Vec<_> = sensors
+        .iter()
+        .flat_map(|s| [s.u() - s.radius, s.u() + s.radius])
+        .collect();
+    let v_lines: Vec<_> = sensors
+        .iter()
+        .flat_map(|s| [s.v() - s.radius, s.v() + s.radius])
+        .collect();
+    let columns = [bounds.min.0, bounds.max.0];
+
+    let mut rows = vec![bounds.min.1, bounds.max.1 + 1];
+    for s in sensors {
+        rows.extend([
+            s.pos.1 - s.radius,
+            s.pos.1,
+            s.pos.1 + 1,
+            s.pos.1 + s.radius + 1,
+        ]);
+    }
+    for offset in -1..=1 {
+        for u in &u_lines {
+            for v in &v_lines {
+                let row = (u - v + offset).div_euclid(2);
+                rows.extend([row, row + 1]);
             }
-            if start > max && end > max {
-                break;
-            } else if end > max {
-                end = max;
+            for x in columns {
+                rows.extend([u - x + offset, u - x + offset + 1]);
+            }
+        }
+        for v in &v_lines {
+            for x in columns {
+                rows.extend([x - v + offset, x - v + offset + 1]);
             }
-
-            vec.push(start..end + 1);
         }
+    }
 
-        vec.sort_by(|left, right| left.start.cmp(&right.start));
-        let merged: Vec<_> = merge_ranges(vec).collect();
-        if merged.len() == 2 {
-            let y = line;
-            let x = merged[0].end;
-            return (x as i64 * 4000000) + y as i64;
+    rows.retain(|y| (bounds.min.1..=bounds.max.1 + 1).contains(y));
+    rows.sort_unstable();
+    rows.dedup();
+    rows
+}
+
+fn covered_area(sensors: &[Sensor], bounds: &Bounds) -> i64 {
+    breakpoints(sensors, bounds)
+        .windows(2)
+        .map(|w| {
+            let (first, last) = (w[0], w[1] - 1);
+            let a = row_covered(sensors, first, bounds);
+            let b = row_covered(sensors, last, bounds);
+            (a + b) * (last - first + 1) / 2
+        })
+        .sum()
+}
+
+fn uncovered_cells(sensors: &[Sensor], bounds: &Bounds) -> Vec<(i64, i64)> {
+    let width = bounds.width();
+    let mut cells = Vec::new();
+    for w in breakpoints(sensors, bounds).windows(2) {
+        let (first, last) = (w[0], w[1] - 1);
+        // Coverage is linear across the segment, so full ends mean full rows
+        if row_covered(sensors, first, bounds) == width
+            && row_covered(sensors, last, bounds) == width
+        {
+            continue;
+        }
+        for y in first..=last {
+            let mut x = bounds.min.0;
+            for (start, end) in row_intervals(sensors, y, bounds) {
+                cells.extend((x..start).map(|x| (x, y)));
+                x = end + 1;
+            }
+            cells.extend((x..=bounds.max.0).map(|x| (x, y)));
         }
     }
+    cells
+}
+
+// A single uncovered cell has to sit right outside some diamond edges, so
+// only the intersections of those edges need to be checked
+fn boundary_candidates(sensors: &[Sensor], bounds: &Bounds) -> HashSet<(i64, i64)> {
+    let u_lines: HashSet<_> = sensors
+        .iter()
+        .flat_map(|s| [s.u() - s.radius - 1, s.u() + s.radius + 1])
+        .collect();
+    let v_lines: HashSet<_> = sensors
+        .iter()
+        .flat_map(|s| [s.v() - s.radius - 1, s.v() + s.radius + 1])
+        .collect();
+
+    u_lines
+        .iter()
+        .cartesian_product(v_lines.iter())
+        .filter(|(u, v)| (*u - *v) % 2 == 0)
+        .map(|(u, v)| ((u + v) / 2, (u - v) / 2))
+        .filter(|point| bounds.contains(*point))
+        .filter(|point| sensors.iter().all(|sensor| !sensor.covers(*point)))
+        .collect()
+}
+
+fn task_one(input: &[String]) -> Answer {
+    let line = 2000000;
+    let sensors = parse_sensors(input);
+    let bounds = Bounds {
+        min: (
+            sensors.iter().map(|s| s.pos.0 - s.radius).min().unwrap(),
+            line,
+        ),
+        max: (
+            sensors.iter().map(|s| s.pos.0 + s.radius).max().unwrap(),
+            line,
+        ),
+    };
+
+    let beacons = sensors
+        .iter()
+        .map(|sensor| sensor.beacon)
+        .filter(|beacon| beacon.1 == line)
+        .unique()
+        .count() as i64;
 
-    unreachable!();
+    (covered_area(&sensors, &bounds) - beacons).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    let max = 4000000;
+    let sensors = parse_sensors(input);
+    let bounds = Bounds {
+        min: (0, 0),
+        max: (max, max),
+    };
+
+    let (x, y) = boundary_candidates(&sensors, &bounds)
+        .into_iter()
+        .exactly_one()
+        .unwrap();
+    (x * 4000000 + y).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
+
+    if let Some(bounds) = get_bounds() {
+        let sensors = parse_sensors(&input);
+        let covered = covered_area(&sensors, &bounds);
+        let uncovered = uncovered_cells(&sensors, &bounds);
+        println!("Covered: {} of {}", covered, bounds.area());
+        println!("Uncovered: {:?}", uncovered);
+    }
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -131,26 +308,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -161,50 +333,6 @@ fn get_input_file() -> String {
         .unwrap_or_else(|| "input".to_string())
 }
 
-// https://codereview.stackexchange.com/questions/103864/merging-an-overlapping-collection-of-intervals
-use std::cmp;
-use std::ops::Range;
-
-struct MergedRanges<I> {
-    values: I,
-    last: Option<Range<i32>>,
-}
-
-fn merge_ranges<I>(iterator: I) -> MergedRanges<I::IntoIter>
-where
-    I: IntoIterator<Item = Range<i32>>,
-{
-    let mut iterator = iterator.into_iter();
-    let last = iterator.next();
-
-    MergedRanges {
-        values: iterator,
-        last,
-    }
-}
-
-impl<I> Iterator for MergedRanges<I>
-where
-    I: Iterator<Item = Range<i32>>,
-{
-    type Item = Range<i32>;
-
-    fn next(&mut self) -> Option<Range<i32>> {
-        // Are we still in the loop?
-        if let Some(mut last) = self.last.clone() {
-            for new in &mut self.values {
-                if last.end < new.start {
-                    self.last = Some(new);
-                    return Some(last);
-                }
-
-                last.end = cmp::max(last.end, new.end);
-            }
-
-            self.last = None;
-            return Some(last);
-        }
-
-        None
-    }
+fn get_bounds() -> Option<Bounds> {
+    std::env::var("BOX").ok().map(|b| b.parse().unwrap())
 }
@@ -0,0 +1,57 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// `size` lines of calibration values. Spelled digits often share letters
+/// with their neighbours, and every line has at least one plain digit
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let mut line = String::new();
+            for _ in 0..rng.range(1..8) {
+                match rng.below(3) {
+                    0 => line.push((b'0' + rng.below(10) as u8) as char),
+                    1 => {
+                        let word = *rng.pick(&WORDS);
+                        line.push_str(word)
+                    }
+                    _ => line.push((b'a' + rng.below(26) as u8) as char),
+                }
+            }
+            let at = rng.below(line.len() + 1);
+            line.insert(at, (b'1' + rng.below(9) as u8) as char);
+            line
+        })
+        .collect()
+}
+
+fn digit_at(s: &str, words: bool) -> Option<usize> {
+    let c = s.chars().next()?;
+    if let Some(d) = c.to_digit(10) {
+        return Some(d as usize);
+    }
+    WORDS
+        .iter()
+        .position(|w| words && s.starts_with(w))
+        .map(|i| i + 1)
+}
+
+// Tries every position from either end
+fn calibration(line: &str, words: bool) -> usize {
+    let first = (0..line.len())
+        .find_map(|i| digit_at(&line[i..], words))
+        .unwrap_or(0);
+    let last = (0..line.len())
+        .rev()
+        .find_map(|i| digit_at(&line[i..], words))
+        .unwrap_or(0);
+    first * 10 + last
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let sum = |words| input.iter().map(|l| calibration(l, words)).sum::<usize>();
+    (sum(false).into(), sum(true).into())
+}
@@ -1,15 +1,149 @@
-use std::str::FromStr;
-
-fn task_one(input: &[String]) -> usize {
-    input.iter().fold(0, |acc, x| {
-        [
-            x.chars().find_map(|c| c.to_digit(10)).unwrap_or(0) as usize,
-            x.chars().rev().find_map(|c| c.to_digit(10)).unwrap_or(0) as usize,
-        ]
-        .iter()
-        .fold(0, |n_acc, it| n_acc * 10 + it)
-            + acc
-    })
+use std::collections::VecDeque;
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+const DIGITS: [(&str, usize); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+const WORDS: [(&str, usize); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+#[derive(Debug, Clone)]
+struct Node {
+    next: [usize; 256],
+    fail: usize,
+    token: Option<usize>,
+    // Nearest node along the failure links where some shorter token ends
+    output: Option<usize>,
+}
+
+// Aho-Corasick automaton that reports every match, overlapping ones included
+#[derive(Debug)]
+struct Scanner {
+    nodes: Vec<Node>,
+    tokens: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Match {
+    start: usize,
+    end: usize,
+    value: usize,
+}
+
+impl Node {
+    fn new() -> Self {
+        Self {
+            next: [usize::MAX; 256],
+            fail: 0,
+            token: None,
+            output: None,
+        }
+    }
+}
+
+impl Scanner {
+    fn new<'a, I>(vocabulary: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a str, usize)>,
+    {
+        let tokens: Vec<_> = vocabulary
+            .into_iter()
+            .map(|(token, value)| (token.to_string(), value))
+            .collect();
+
+        let mut nodes = vec![Node::new()];
+        for (idx, (token, _)) in tokens.iter().enumerate() {
+            let mut node = 0;
+            for b in token.bytes() {
+                if nodes[node].next[b as usize] == usize::MAX {
+                    nodes[node].next[b as usize] = nodes.len();
+                    nodes.push(Node::new());
+                }
+                node = nodes[node].next[b as usize];
+            }
+            nodes[node].token = Some(idx);
+        }
+
+        // Breadth first so failure links always point to finished nodes. Missing
+        // edges are filled in to make the automaton a complete DFA
+        let mut queue = VecDeque::new();
+        for b in 0..256 {
+            match nodes[0].next[b] {
+                usize::MAX => nodes[0].next[b] = 0,
+                child => queue.push_back(child),
+            }
+        }
+        while let Some(node) = queue.pop_front() {
+            for b in 0..256 {
+                let fail = nodes[nodes[node].fail].next[b];
+                match nodes[node].next[b] {
+                    usize::MAX => nodes[node].next[b] = fail,
+                    child => {
+                        nodes[child].fail = fail;
+                        nodes[child].output = match nodes[fail].token {
+                            Some(_) => Some(fail),
+                            None => nodes[fail].output,
+                        };
+                        queue.push_back(child);
+                    }
+                }
+            }
+        }
+
+        Self { nodes, tokens }
+    }
+
+    fn find_iter<'a>(&'a self, s: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut node = 0;
+        s.bytes().enumerate().flat_map(move |(idx, b)| {
+            node = self.nodes[node].next[b as usize];
+            let first = match self.nodes[node].token {
+                Some(_) => Some(node),
+                None => self.nodes[node].output,
+            };
+            std::iter::successors(first, |n| self.nodes[*n].output).map(move |n| {
+                let (text, value) = &self.tokens[self.nodes[n].token.unwrap()];
+                Match {
+                    start: idx + 1 - text.len(),
+                    end: idx + 1,
+                    value: *value,
+                }
+            })
+        })
+    }
+
+    // Earliest and latest starting match, preferring the longest on ties
+    fn first_and_last(&self, s: &str) -> Option<(Match, Match)> {
+        let matches: Vec<_> = self.find_iter(s).collect();
+        let first = matches
+            .iter()
+            .min_by_key(|m| (m.start, std::cmp::Reverse(m.end)))?;
+        let last = matches.iter().max_by_key(|m| (m.start, m.end))?;
+        Some((*first, *last))
+    }
 }
 
 #[derive(Debug)]
@@ -18,61 +152,50 @@ struct Calibration {
     second: usize,
 }
 
-impl FromStr for Calibration {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let chars = s.chars().collect::<Vec<char>>();
-        let window_len = if chars.len() >= 5 { 5 } else { chars.len() };
-        let first_try = chars.windows(window_len).find_map(|s| match s {
-            ['o', 'n', 'e', ..] => Some(1),
-            ['t', 'w', 'o', ..] => Some(2),
-            [x, 'o', 'n', 'e', ..] => Some(x.to_digit(10).unwrap_or(1)),
-            [x, 't', 'w', 'o', ..] => Some(x.to_digit(10).unwrap_or(2)),
-            ['t', 'h', 'r', 'e', 'e'] => Some(3),
-            ['f', 'o', 'u', 'r', ..] => Some(4),
-            ['f', 'i', 'v', 'e', ..] => Some(5),
-            ['s', 'i', 'x', ..] => Some(6),
-            [x, 's', 'i', 'x', ..] => Some(x.to_digit(10).unwrap_or(6)),
-            ['s', 'e', 'v', 'e', 'n'] => Some(7),
-            ['e', 'i', 'g', 'h', 't'] => Some(8),
-            ['n', 'i', 'n', 'e', ..] => Some(9),
-            _ => s.iter().find_map(|c| c.to_digit(10)),
-        });
-
-        let second_try = chars.windows(window_len).rev().find_map(|s| match s {
-            [.., 'o', 'n', 'e'] => Some(1),
-            [.., 't', 'w', 'o'] => Some(2),
-            [.., 'o', 'n', 'e', x] => Some(x.to_digit(10).unwrap_or(1)),
-            [.., 't', 'w', 'o', x] => Some(x.to_digit(10).unwrap_or(2)),
-            ['t', 'h', 'r', 'e', 'e'] => Some(3),
-            [.., 'f', 'o', 'u', 'r'] => Some(4),
-            [.., 'f', 'i', 'v', 'e'] => Some(5),
-            [.., 's', 'i', 'x'] => Some(6),
-            [.., 's', 'i', 'x', x] => Some(x.to_digit(10).unwrap_or(6)),
-            ['s', 'e', 'v', 'e', 'n'] => Some(7),
-            ['e', 'i', 'g', 'h', 't'] => Some(8),
-            [.., 'n', 'i', 'n', 'e'] => Some(9),
-            _ => s.iter().rev().find_map(|c| c.to_digit(10)),
-        });
-
-        let first = first_try.unwrap_or_else(|| second_try.unwrap()) as usize;
-        let second = second_try.unwrap_or_else(|| first_try.unwrap()) as usize;
-
-        Ok(Calibration { first, second })
+impl Calibration {
+    fn scan(s: &str, scanner: &Scanner) -> Self {
+        let (first, second) = scanner
+            .first_and_last(s)
+            .map_or((0, 0), |(first, last)| (first.value, last.value));
+        Calibration { first, second }
     }
 }
 
-fn task_two(input: &[String]) -> usize {
+fn sum_calibrations(input: &[String], scanner: &Scanner) -> usize {
     input.iter().fold(0, |acc, s| {
-        let c = Calibration::from_str(s).unwrap();
+        let c = Calibration::scan(s, scanner);
         acc + c.first * 10 + c.second
     })
 }
 
+fn task_one(input: &[String]) -> Answer {
+    sum_calibrations(input, &Scanner::new(DIGITS)).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    match get_vocabulary() {
+        Some(vocabulary) => {
+            let vocabulary = vocabulary
+                .iter()
+                .map(|(token, value)| (token.as_str(), *value));
+            sum_calibrations(input, &Scanner::new(DIGITS.into_iter().chain(vocabulary))).into()
+        }
+        None => sum_calibrations(input, &Scanner::new(DIGITS.into_iter().chain(WORDS))).into(),
+    }
+}
+
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -86,26 +209,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -115,3 +233,15 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+// Extra tokens as `token value` lines, e.g. `en 1` to read Norwegian
+fn get_vocabulary() -> Option<Vec<(String, usize)>> {
+    let path = std::env::var("VOCAB").ok()?;
+    let vocabulary = std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter_map(|l| l.split_once(' '))
+        .map(|(token, value)| (token.to_string(), value.trim().parse().unwrap()))
+        .collect();
+    Some(vocabulary)
+}
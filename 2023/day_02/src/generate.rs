@@ -0,0 +1,43 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+/// `size` games of up to six draws, each showing some of the colours
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (1..=size)
+        .map(|id| {
+            let draws: Vec<String> = (0..rng.range(1..7))
+                .map(|_| {
+                    let mut colours = COLOURS.to_vec();
+                    rng.shuffle(&mut colours);
+                    let shown = rng.range(1..4) as usize;
+                    colours[..shown]
+                        .iter()
+                        .map(|c| format!("{} {}", rng.range(1..18), c))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .collect();
+            format!("Game {}: {}", id, draws.join("; "))
+        })
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let (mut possible, mut power) = (0, 0);
+    for line in input {
+        let (game, draws) = line.split_once(": ").unwrap();
+        let mut fewest = [0; 3];
+        for cubes in draws.split([';', ',']) {
+            let (count, colour) = cubes.trim().split_once(' ').unwrap();
+            let i = COLOURS.iter().position(|c| *c == colour).unwrap();
+            fewest[i] = fewest[i].max(count.parse().unwrap());
+        }
+        if fewest[0] <= 12 && fewest[1] <= 13 && fewest[2] <= 14 {
+            possible += game["Game ".len()..].parse::<usize>().unwrap();
+        }
+        power += fewest.iter().product::<usize>();
+    }
+    (possible.into(), power.into())
+}
@@ -1,5 +1,10 @@
 use std::str::FromStr;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Debug)]
 struct Game {
     id: usize,
@@ -55,43 +60,57 @@ impl FromStr for Game {
     }
 }
 
-fn task_one(input: &[String]) -> usize {
-    input.iter().fold(0, |acc, s| {
-        let game = Game::from_str(s).unwrap();
-        match game
-            .cubes
-            .iter()
-            .find(|c| c.red > 12 || c.green > 13 || c.blue > 14)
-        {
-            Some(_) => acc,
-            None => acc + game.id,
-        }
-    })
+fn task_one(input: &[String]) -> Answer {
+    input
+        .iter()
+        .fold(0, |acc, s| {
+            let game = Game::from_str(s).unwrap();
+            match game
+                .cubes
+                .iter()
+                .find(|c| c.red > 12 || c.green > 13 || c.blue > 14)
+            {
+                Some(_) => acc,
+                None => acc + game.id,
+            }
+        })
+        .into()
 }
 
-fn task_two(input: &[String]) -> usize {
-    input.iter().fold(0, |acc, s| {
-        let game = Game::from_str(s).unwrap();
-        let cubes = game
-            .cubes
-            .into_iter()
-            .reduce(|mut c_acc, c| {
-                c_acc.green = c.green.max(c_acc.green);
-                c_acc.red = c.red.max(c_acc.red);
-                c_acc.blue = c.blue.max(c_acc.blue);
-
-                c_acc
-            })
-            .unwrap();
+fn task_two(input: &[String]) -> Answer {
+    input
+        .iter()
+        .fold(0, |acc, s| {
+            let game = Game::from_str(s).unwrap();
+            let cubes = game
+                .cubes
+                .into_iter()
+                .reduce(|mut c_acc, c| {
+                    c_acc.green = c.green.max(c_acc.green);
+                    c_acc.red = c.red.max(c_acc.red);
+                    c_acc.blue = c.blue.max(c_acc.blue);
+
+                    c_acc
+                })
+                .unwrap();
 
-        acc + (cubes.red * cubes.blue * cubes.green)
-    })
+            acc + (cubes.red * cubes.blue * cubes.green)
+        })
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -105,26 +124,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,87 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const SYMBOLS: [char; 10] = ['*', '*', '#', '+', '$', '/', '@', '=', '%', '&'];
+
+/// An engine schematic `size` cells on a side, with part numbers of up to
+/// three digits and symbols scattered between them
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(3);
+    (0..size)
+        .map(|_| {
+            let mut row = String::new();
+            while row.len() < size {
+                match rng.below(10) {
+                    0..=1 => {
+                        let digits = rng.range(1..4) as usize;
+                        let number = rng.range(1..1000).to_string();
+                        row.push_str(&number[..digits.min(number.len())]);
+                    }
+                    2 => row.push(*rng.pick(&SYMBOLS)),
+                    _ => row.push('.'),
+                }
+            }
+            // Cutting a number at the edge keeps the grid square
+            row.truncate(size);
+            row
+        })
+        .collect()
+}
+
+// Every number as its row, first and last column and value
+fn numbers(grid: &[Vec<char>]) -> Vec<(usize, usize, usize, usize)> {
+    let mut numbers = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            if !row[x].is_ascii_digit() {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < row.len() && row[x].is_ascii_digit() {
+                x += 1;
+            }
+            let value = row[start..x].iter().collect::<String>().parse().unwrap();
+            numbers.push((y, start, x - 1, value));
+        }
+    }
+    numbers
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let grid: Vec<Vec<char>> = input.iter().map(|l| l.chars().collect()).collect();
+    let numbers = numbers(&grid);
+    let touches = |(y, start, end, _): (usize, usize, usize, usize), (sx, sy): (usize, usize)| {
+        y.abs_diff(sy) <= 1 && sx + 1 >= start && sx <= end + 1
+    };
+
+    let symbols: Vec<(usize, usize, char)> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, c)| (x, y, *c)))
+        .filter(|(_, _, c)| !c.is_ascii_digit() && *c != '.')
+        .collect();
+    let parts: usize = numbers
+        .iter()
+        .filter(|n| symbols.iter().any(|(x, y, _)| touches(**n, (*x, *y))))
+        .map(|n| n.3)
+        .sum();
+    let ratios: usize = symbols
+        .iter()
+        .filter(|(_, _, c)| *c == '*')
+        .map(|(x, y, _)| {
+            let near: Vec<usize> = numbers
+                .iter()
+                .filter(|n| touches(**n, (*x, *y)))
+                .map(|n| n.3)
+                .collect();
+            if near.len() == 2 {
+                near[0] * near[1]
+            } else {
+                0
+            }
+        })
+        .sum();
+    (parts.into(), ratios.into())
+}
@@ -2,13 +2,18 @@ use std::collections::HashSet;
 
 use day_03::matrix::Matrix;
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 fn is_adjacent(matrix: &Matrix<char>, w: usize, h: usize) -> bool {
     matrix
         .iter_adjacent(w, h)
         .any(|pos| !matrix[pos].is_ascii_digit() && matrix[pos] != '.')
 }
 
-fn task_one(input: &[String]) -> usize {
+fn task_one(input: &[String]) -> Answer {
     let matrix = Matrix::new(
         input.iter().flat_map(|s| s.chars()).collect(),
         (input[0].len(), input.len()),
@@ -42,7 +47,7 @@ fn task_one(input: &[String]) -> usize {
         }
     }
 
-    sum
+    sum.into()
 }
 
 fn find_number_at_pos(matrix: &Matrix<char>, idx: (usize, usize)) -> ((usize, usize), usize) {
@@ -72,7 +77,7 @@ fn find_adjecent_numbers(matrix: &Matrix<char>, w: usize, h: usize) -> Vec<usize
         .collect()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     let matrix = Matrix::new(
         input.iter().flat_map(|s| s.chars()).collect(),
         (input[0].len(), input.len()),
@@ -93,13 +98,21 @@ fn task_two(input: &[String]) -> usize {
         }
     }
 
-    sum
+    sum.into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -113,26 +126,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
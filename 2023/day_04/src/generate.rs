@@ -0,0 +1,59 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` scratchcards with five winning numbers and eight numbers drawn
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (1..=size)
+        .map(|card| {
+            let mut pool: Vec<i64> = (1..100).collect();
+            rng.shuffle(&mut pool);
+            let winning = &pool[..5];
+            // Drawing from a small pool around the winning numbers gives
+            // plenty of matches
+            let mut candidates = pool[..rng.range(8..20) as usize].to_vec();
+            rng.shuffle(&mut candidates);
+            let column = |numbers: &[i64]| {
+                numbers
+                    .iter()
+                    .map(|n| format!("{:>2}", n))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            format!(
+                "Card {:>3}: {} | {}",
+                card,
+                column(winning),
+                column(&candidates[..8])
+            )
+        })
+        .collect()
+}
+
+// Plays every won copy one at a time, which is slow but leaves nothing to
+// get wrong
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let matches: Vec<usize> = input
+        .iter()
+        .map(|l| {
+            let (winning, drawn) = l.split_once(": ").unwrap().1.split_once(" | ").unwrap();
+            let winning: Vec<&str> = winning.split_whitespace().collect();
+            drawn
+                .split_whitespace()
+                .filter(|n| winning.contains(n))
+                .count()
+        })
+        .collect();
+    let points: usize = matches
+        .iter()
+        .filter(|m| **m > 0)
+        .map(|m| 1 << (m - 1))
+        .sum();
+
+    let mut pending: Vec<usize> = (0..matches.len()).collect();
+    let mut cards = 0;
+    while let Some(card) = pending.pop() {
+        cards += 1;
+        pending.extend(card + 1..(card + 1 + matches[card]).min(matches.len()));
+    }
+    (points.into(), cards.into())
+}
@@ -1,11 +1,39 @@
-use std::{
-    collections::{HashMap, HashSet},
-    str::FromStr,
-};
+use std::str::FromStr;
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+#[derive(Debug, Default)]
+struct Bitset(Vec<u64>);
 
 struct Game {
-    winning_numbers: HashSet<usize>,
-    my_numbers: HashSet<usize>,
+    winning_numbers: Bitset,
+    my_numbers: Bitset,
+}
+
+impl FromIterator<usize> for Bitset {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        let mut words = Vec::new();
+        for num in iter {
+            if words.len() <= num / 64 {
+                words.resize(num / 64 + 1, 0);
+            }
+            words[num / 64] |= 1 << (num % 64);
+        }
+        Self(words)
+    }
+}
+
+impl Bitset {
+    fn intersection_count(&self, other: &Bitset) -> usize {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
 }
 
 impl FromStr for Game {
@@ -17,12 +45,12 @@ impl FromStr for Game {
         let winning_numbers = winning_numbers
             .split_whitespace()
             .map(|num| num.parse::<usize>().unwrap())
-            .collect::<HashSet<usize>>();
+            .collect::<Bitset>();
 
         let my_numbers = my_numbers
             .split_whitespace()
             .map(|num| num.parse::<usize>().unwrap())
-            .collect::<HashSet<usize>>();
+            .collect::<Bitset>();
 
         Ok(Self {
             winning_numbers,
@@ -31,51 +59,97 @@ impl FromStr for Game {
     }
 }
 
-fn task_one(input: &[String]) -> usize {
+impl Game {
+    fn matches(&self) -> usize {
+        self.winning_numbers.intersection_count(&self.my_numbers)
+    }
+}
+
+fn get_matches(input: &[String]) -> Vec<usize> {
     input
         .iter()
-        .map(|s| {
-            let game = Game::from_str(s).unwrap();
-            let count = game.winning_numbers.intersection(&game.my_numbers).count();
+        .map(|s| Game::from_str(s).unwrap().matches())
+        .collect()
+}
+
+// Cards only ever win copies of later cards, so a single forward pass
+// settles every count
+fn copies(matches: &[usize]) -> Vec<usize> {
+    let mut copies = vec![1; matches.len()];
+    for (idx, count) in matches.iter().enumerate() {
+        let end = (idx + 1 + count).min(matches.len());
+        for next in idx + 1..end {
+            copies[next] += copies[idx];
+        }
+    }
+    copies
+}
+
+// How many copies of `card` a single instance of each card ends up producing.
+// Summed over all cards this gives the total number of copies of `card`
+fn provenance(matches: &[usize], card: usize) -> Vec<usize> {
+    let mut produced = vec![0; matches.len()];
+    produced[card] = 1;
+    for idx in (0..card).rev() {
+        let end = (idx + 1 + matches[idx]).min(card + 1);
+        produced[idx] = produced[idx + 1..end].iter().sum();
+    }
+    produced
+}
+
+fn task_one(input: &[String]) -> Answer {
+    get_matches(input)
+        .into_iter()
+        .map(|count| {
             if count > 0 {
                 2_usize.pow(count as u32 - 1)
             } else {
                 0
             }
         })
-        .sum()
+        .sum::<usize>()
+        .into()
 }
 
-fn task_two(input: &[String]) -> usize {
-    input
-        .iter()
-        .enumerate()
-        .fold(HashMap::new(), |mut acc, (idx, s)| {
-            let game = Game::from_str(s).unwrap();
-            let count = game.winning_numbers.intersection(&game.my_numbers).count();
-            let current = idx + 1;
-
-            let cards = acc
-                .entry(current)
-                .and_modify(|e| *e += 1)
-                .or_insert(1)
-                .to_owned();
-
-            let next = current + 1;
-            for i in next..count + next {
-                acc.entry(i).and_modify(|e| *e += cards).or_insert(cards);
-            }
+fn task_two(input: &[String]) -> Answer {
+    let matches = get_matches(input);
+    match get_provenance() {
+        Some(card) if (1..=matches.len()).contains(&card) => print_provenance(&matches, card),
+        Some(card) => eprintln!(
+            "PROVENANCE={} is not a card, expected 1 to {}",
+            card,
+            matches.len()
+        ),
+        None => (),
+    }
+    copies(&matches).iter().sum::<usize>().into()
+}
 
-            acc
-        })
-        .values()
-        .sum()
+fn print_provenance(matches: &[usize], card: usize) {
+    for (idx, produced) in provenance(matches, card - 1).iter().enumerate() {
+        if *produced > 0 {
+            println!(
+                "Card {}: {} cop{}",
+                idx + 1,
+                produced,
+                if *produced == 1 { "y" } else { "ies" }
+            );
+        }
+    }
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -89,26 +163,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -118,3 +187,7 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn get_provenance() -> Option<usize> {
+    std::env::var("PROVENANCE").ok().map(|n| n.parse().unwrap())
+}
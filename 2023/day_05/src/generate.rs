@@ -0,0 +1,84 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const MAPS: [&str; 7] = [
+    "seed-to-soil",
+    "soil-to-fertilizer",
+    "fertilizer-to-water",
+    "water-to-light",
+    "light-to-temperature",
+    "temperature-to-humidity",
+    "humidity-to-location",
+];
+
+/// An almanac of `size` seed ranges of up to fifty seeds each, and maps of a
+/// few ranges that never overlap their own sources
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let seeds: Vec<String> = (0..size.max(1))
+        .map(|_| format!("{} {}", rng.range(0..1000), rng.range(1..50)))
+        .collect();
+    let mut lines = vec![format!("seeds: {}", seeds.join(" "))];
+    for map in MAPS {
+        lines.push(String::new());
+        lines.push(format!("{} map:", map));
+        let mut source = rng.range(0..100);
+        for _ in 0..rng.range(1..6) {
+            let length = rng.range(1..300);
+            lines.push(format!("{} {} {}", rng.range(0..1200), source, length));
+            source += length + rng.range(0..100);
+        }
+    }
+    lines
+}
+
+// Pushes whole ranges through each map, splitting them wherever they cross
+// the edge of a map range
+fn lowest(seeds: Vec<(usize, usize)>, maps: &[Vec<(usize, usize, usize)>]) -> usize {
+    let mut ranges = seeds;
+    for map in maps {
+        let mut mapped = Vec::new();
+        while let Some((start, end)) = ranges.pop() {
+            let hit = map
+                .iter()
+                .find(|(_, source, length)| start < source + length && *source < end);
+            match hit {
+                Some((dest, source, length)) => {
+                    let (from, to) = (start.max(*source), end.min(source + length));
+                    mapped.push((from - source + dest, to - source + dest));
+                    if start < from {
+                        ranges.push((start, from));
+                    }
+                    if to < end {
+                        ranges.push((to, end));
+                    }
+                }
+                None => mapped.push((start, end)),
+            }
+        }
+        ranges = mapped;
+    }
+    ranges.iter().map(|r| r.0).min().unwrap()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let seeds: Vec<usize> = input[0]
+        .split(' ')
+        .skip(1)
+        .map(|s| s.parse().unwrap())
+        .collect();
+    let maps: Vec<Vec<(usize, usize, usize)>> = input[2..]
+        .split(|l| l.is_empty())
+        .map(|block| {
+            block[1..]
+                .iter()
+                .map(|l| {
+                    let v: Vec<usize> = l.split(' ').map(|n| n.parse().unwrap()).collect();
+                    (v[0], v[1], v[2])
+                })
+                .collect()
+        })
+        .collect();
+    let single = seeds.iter().map(|s| (*s, s + 1)).collect();
+    let ranges = seeds.chunks(2).map(|r| (r[0], r[0] + r[1])).collect();
+    (lowest(single, &maps).into(), lowest(ranges, &maps).into())
+}
@@ -0,0 +1,47 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// Up to four races, as many as `size` allows, with records that can
+/// mostly be beaten
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let races: Vec<(i64, i64)> = (0..size.clamp(1, 4))
+        .map(|_| {
+            let time = rng.range(1..100);
+            (time, rng.range(0..time * time / 4 + 2))
+        })
+        .collect();
+    let row = |name: &str, values: Vec<i64>| {
+        let values: Vec<String> = values.iter().map(|v| format!("{:>5}", v)).collect();
+        format!("{:<9}{}", name, values.join(" "))
+    };
+    vec![
+        row("Time:", races.iter().map(|r| r.0).collect()),
+        row("Distance:", races.iter().map(|r| r.1).collect()),
+    ]
+}
+
+fn ways(time: u64, record: u64) -> u64 {
+    (0..=time)
+        .filter(|hold| hold * (time - hold) > record)
+        .count() as u64
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let values = |line: &String| {
+        line.split_whitespace()
+            .skip(1)
+            .map(String::from)
+            .collect::<Vec<_>>()
+    };
+    let (times, records) = (values(&input[0]), values(&input[1]));
+    let product: u64 = times
+        .iter()
+        .zip(&records)
+        .map(|(t, r)| ways(t.parse().unwrap(), r.parse().unwrap()))
+        .product();
+    let joined = ways(
+        times.concat().parse().unwrap(),
+        records.concat().parse().unwrap(),
+    );
+    (product.into(), joined.into())
+}
@@ -1,35 +1,125 @@
-fn task_one(input: &[String]) -> usize {
+use std::ops::RangeInclusive;
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above converges to the floor of the root
+    let mut x = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + n / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+// Overflowing means the distance is larger than any record that fits
+fn beats(time: u128, distance: u128, hold: u128) -> bool {
+    hold.checked_mul(time - hold).is_none_or(|d| d > distance)
+}
+
+// Smallest hold time beating the record, if any. The distance is symmetric
+// around `time / 2`, so the interval ends at `time - lo`
+fn lowest_winning(time: u128, distance: u128) -> Option<u128> {
+    let half = time / 2;
+    if !beats(time, distance, half) {
+        return None;
+    }
+
+    let discriminant = time
+        .checked_mul(time)
+        .and_then(|t| t.checked_sub(distance.checked_mul(4)?));
+    let mut lo = match discriminant {
+        Some(discriminant) => (time - isqrt(discriminant).min(time)) / 2,
+        // Too large for the closed form, so binary search the rising half instead
+        None => {
+            let (mut lo, mut hi) = (0, half);
+            while lo < hi {
+                let mid = lo + (hi - lo) / 2;
+                if beats(time, distance, mid) {
+                    hi = mid;
+                } else {
+                    lo = mid + 1;
+                }
+            }
+            lo
+        }
+    };
+
+    // Floor rounding of the root might land one off in either direction
+    while !beats(time, distance, lo) {
+        lo += 1;
+    }
+    while lo > 0 && beats(time, distance, lo - 1) {
+        lo -= 1;
+    }
+    Some(lo)
+}
+
+fn winning_holds(time: u128, distance: u128) -> Option<RangeInclusive<u128>> {
+    let interval = lowest_winning(time, distance).map(|lo| lo..=time - lo);
+    if check() {
+        let brute: Vec<_> = (0..=time).filter(|i| beats(time, distance, *i)).collect();
+        assert_eq!(
+            interval.clone().map(|i| (*i.start(), *i.end())),
+            brute.first().zip(brute.last()).map(|(a, b)| (*a, *b)),
+            "time {} distance {}",
+            time,
+            distance
+        );
+    }
+    interval
+}
+
+fn ways_to_win(time: u128, distance: u128) -> u128 {
+    winning_holds(time, distance).map_or(0, |holds| holds.end() - holds.start() + 1)
+}
+
+fn task_one(input: &[String]) -> Answer {
     let f = |i: usize| {
         input[i]
             .split_whitespace()
-            .filter_map(|x| x.parse::<usize>().ok())
+            .filter_map(|x| x.parse::<u128>().ok())
     };
 
     f(0).zip(f(1))
-        .map(|(time, distance)| (0..time).filter(|i| (i * (time - i)) > distance).count())
-        .product()
+        .map(|(time, distance)| ways_to_win(time, distance))
+        .product::<u128>()
+        .into()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     let f = |i: usize| {
         input[i]
             .split_whitespace()
-            .filter(|s| s.parse::<i32>().is_ok())
+            .filter(|s| s.parse::<u128>().is_ok())
             .collect::<String>()
-            .parse::<usize>()
+            .parse::<u128>()
             .unwrap()
     };
 
-    let time = f(0);
-    let distance = f(1);
-
-    (0..time).filter(|i| (i * (time - i)) > distance).count()
+    ways_to_win(f(0), f(1)).into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -43,26 +133,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -72,3 +157,7 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn check() -> bool {
+    std::env::var("CHECK").is_ok()
+}
@@ -0,0 +1,66 @@
+use std::collections::HashSet;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const CARDS: &str = "23456789TJQKA";
+
+/// `size` different hands with their bids. Hands mostly repeat a few labels
+/// so every hand type shows up
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let labels: Vec<char> = CARDS.chars().collect();
+    let mut seen = HashSet::new();
+    let mut lines = Vec::new();
+    while lines.len() < size.clamp(1, 1000) {
+        let mut choice = labels.clone();
+        rng.shuffle(&mut choice);
+        let few = &choice[..rng.range(1..6) as usize];
+        let hand: String = (0..5)
+            .map(|_| if rng.chance(0.2) { 'J' } else { *rng.pick(few) })
+            .collect();
+        if seen.insert(hand.clone()) {
+            lines.push(format!("{} {}", hand, rng.range(1..1001)));
+        }
+    }
+    lines
+}
+
+// The sorted group sizes order the hand types, larger groups first
+fn strength(hand: &str) -> Vec<usize> {
+    let mut groups: Vec<usize> = CARDS
+        .chars()
+        .map(|c| hand.matches(c).count())
+        .filter(|n| *n > 0)
+        .collect();
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    groups
+}
+
+// With jokers every replacement card is tried, and the joker itself ranks
+// lowest when breaking ties
+fn winnings(input: &[String], jokers: bool) -> usize {
+    let order = if jokers { "J23456789TQKA" } else { CARDS };
+    let mut hands: Vec<(Vec<usize>, Vec<usize>, usize)> = input
+        .iter()
+        .map(|l| {
+            let (hand, bid) = l.split_once(' ').unwrap();
+            let kind = if jokers {
+                CARDS
+                    .chars()
+                    .map(|c| strength(&hand.replace('J', &c.to_string())))
+                    .max()
+                    .unwrap()
+            } else {
+                strength(hand)
+            };
+            let ranks = hand.chars().map(|c| order.find(c).unwrap()).collect();
+            (kind, ranks, bid.parse().unwrap())
+        })
+        .collect();
+    hands.sort();
+    hands.iter().enumerate().map(|(i, h)| (i + 1) * h.2).sum()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    (winnings(input, false).into(), winnings(input, true).into())
+}
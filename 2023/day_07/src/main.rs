@@ -1,3 +1,10 @@
+use std::fmt;
+
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Eq, PartialEq, PartialOrd, Ord, Copy, Clone, Debug)]
 enum HandType {
     HighCard,
@@ -9,255 +16,216 @@ enum HandType {
     FiveOfAKind,
 }
 
-#[derive(Eq, PartialEq, PartialOrd, Ord, Copy, Clone, Debug)]
-enum CardPartOne {
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    T,
-    J,
-    Q,
-    K,
-    A,
+#[derive(Debug, Clone)]
+struct WildPolicy {
+    cards: Vec<char>,
+    rank_low: bool,
 }
 
-#[derive(Eq, PartialEq, PartialOrd, Ord, Copy, Clone, Debug)]
-enum CardPartTwo {
-    J,
-    Two,
-    Three,
-    Four,
-    Five,
-    Six,
-    Seven,
-    Eight,
-    Nine,
-    T,
-    Q,
-    K,
-    A,
+#[derive(Debug, Clone)]
+struct Rules {
+    // Card labels from weakest to strongest
+    order: Vec<char>,
+    wild: WildPolicy,
 }
 
-trait FromChar {
-    fn from_char(c: char) -> Self;
+#[derive(Debug, Eq, PartialEq)]
+struct Hand {
+    labels: String,
+    ranks: Vec<usize>,
+    bid: usize,
+    hand_type: HandType,
+    substitution: Option<char>,
 }
 
-macro_rules! impl_from_char {
-    ($ty:tt) => {
-        impl FromChar for $ty {
-            fn from_char(c: char) -> Self {
-                match c {
-                    '2' => Self::Two,
-                    '3' => Self::Three,
-                    '4' => Self::Four,
-                    '5' => Self::Five,
-                    '6' => Self::Six,
-                    '7' => Self::Seven,
-                    '8' => Self::Eight,
-                    '9' => Self::Nine,
-                    'T' => Self::T,
-                    'J' => Self::J,
-                    'Q' => Self::Q,
-                    'K' => Self::K,
-                    'A' => Self::A,
-                    _ => unreachable!(),
-                }
-            }
-        }
-    };
-}
+impl WildPolicy {
+    fn none() -> Self {
+        Self::ranks(&[], false)
+    }
 
-impl_from_char!(CardPartOne);
-impl_from_char!(CardPartTwo);
+    fn jokers_low() -> Self {
+        Self::ranks(&['J'], true)
+    }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Hand<T> {
-    cards: [T; 5],
-    bid: usize,
-    hand_type: Option<HandType>,
-}
+    fn jokers_as_any() -> Self {
+        Self::ranks(&['J'], false)
+    }
 
-impl<T> Ord for Hand<T>
-where
-    T: std::cmp::Eq + std::cmp::Ord,
-{
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.hand_type
-            .cmp(&other.hand_type)
-            .then_with(|| self.cards.iter().cmp(other.cards.iter()))
+    fn ranks(cards: &[char], rank_low: bool) -> Self {
+        Self {
+            cards: cards.to_vec(),
+            rank_low,
+        }
     }
-}
 
-impl<T> PartialOrd for Hand<T>
-where
-    T: std::cmp::PartialEq + std::cmp::Ord,
-{
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(std::cmp::Ord::cmp(self, other))
+    fn is_wild(&self, c: char) -> bool {
+        self.cards.contains(&c)
     }
 }
 
-fn fill_cards<T>(cards: &mut [T; 5], s: &str)
-where
-    T: FromChar,
-{
-    for (idx, c) in s.chars().enumerate() {
-        cards[idx] = T::from_char(c);
+impl std::str::FromStr for WildPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "none" => Ok(Self::none()),
+            None if s == "jokers-low" => Ok(Self::jokers_low()),
+            None if s == "jokers-as-any" => Ok(Self::jokers_as_any()),
+            Some(("low", cards)) => Ok(Self::ranks(&cards.chars().collect::<Vec<_>>(), true)),
+            Some(("any", cards)) => Ok(Self::ranks(&cards.chars().collect::<Vec<_>>(), false)),
+            _ => Err(format!("Invalid wildcard policy {}", s)),
+        }
     }
 }
 
-impl<T> Hand<T>
-where
-    T: std::cmp::Eq,
-{
-    fn new(cards: [T; 5], bid: usize) -> Self {
-        Hand {
-            cards,
-            bid,
-            hand_type: None,
+impl Rules {
+    fn new(order: &str, wild: WildPolicy) -> Self {
+        Self {
+            order: order.chars().collect(),
+            wild,
         }
     }
 
-    fn set_hand(&mut self, dedup: &[T], duplicates: &[T]) {
-        match (dedup.len(), duplicates.len()) {
-            (5, 0) => self.hand_type = Some(HandType::HighCard),
-            (1, 4) => self.hand_type = Some(HandType::FiveOfAKind),
-            (4, 1) => self.hand_type = Some(HandType::OnePair),
-            (2, 3) => {
-                if duplicates[0] == duplicates[1] && duplicates[1] == duplicates[2] {
-                    self.hand_type = Some(HandType::FourOfAKind)
-                } else {
-                    self.hand_type = Some(HandType::FullHouse)
-                }
-            }
-            (3, 2) => {
-                if duplicates[0] == duplicates[1] {
-                    self.hand_type = Some(HandType::ThreeOfAKind)
-                } else {
-                    self.hand_type = Some(HandType::TwoPair)
-                }
-            }
-            _ => unreachable!(),
-        };
+    fn rank(&self, c: char) -> usize {
+        let natural = self.order.iter().position(|o| *o == c).unwrap();
+        // Low wildcards are pushed below every natural card while keeping
+        // their relative order
+        match (self.wild.rank_low, self.wild.is_wild(c)) {
+            (true, true) => natural,
+            (true, false) => natural + self.order.len(),
+            (false, _) => natural,
+        }
     }
-}
 
-impl Hand<CardPartTwo> {
-    fn set_custom_hand(&mut self) {
-        let mut sorted_cards: Vec<_> = self
-            .cards
-            .into_iter()
-            .filter(|c| *c != CardPartTwo::J)
-            .collect();
-
-        sorted_cards.sort();
-        let number_of_jokers = 5 - sorted_cards.len();
-
-        let (dedup, duplicates) = unique_and_dupes(&sorted_cards);
-        match (number_of_jokers, dedup.len(), duplicates.len()) {
-            (5, _, _) => self.hand_type = Some(HandType::FiveOfAKind),
-            (4, _, _) => self.hand_type = Some(HandType::FiveOfAKind),
-
-            (3, 2, 0) => self.hand_type = Some(HandType::FourOfAKind),
-            (3, 1, 1) => self.hand_type = Some(HandType::FiveOfAKind),
-
-            (2, 3, 0) => self.hand_type = Some(HandType::ThreeOfAKind),
-            (2, 2, 1) => self.hand_type = Some(HandType::FourOfAKind),
-            (2, 1, 2) => self.hand_type = Some(HandType::FiveOfAKind),
-
-            (1, 4, 0) => self.hand_type = Some(HandType::OnePair),
-            (1, 3, 1) => self.hand_type = Some(HandType::ThreeOfAKind),
-            (1, 1, 3) => self.hand_type = Some(HandType::FiveOfAKind),
-            (1, 2, 2) => {
-                if duplicates[0] == duplicates[1] {
-                    self.hand_type = Some(HandType::FourOfAKind)
-                } else {
-                    self.hand_type = Some(HandType::FullHouse)
-                }
+    fn classify(&self, labels: &str) -> (HandType, Option<char>) {
+        let mut counts: Vec<(usize, char)> = Vec::new();
+        let mut wilds = 0;
+        for c in labels.chars() {
+            if self.wild.is_wild(c) {
+                wilds += 1;
+            } else if let Some(entry) = counts.iter_mut().find(|(_, l)| *l == c) {
+                entry.0 += 1;
+            } else {
+                counts.push((1, c));
             }
+        }
 
-            // Default cases. No jokers
-            _ => self.set_hand(&dedup, &duplicates),
+        // Wildcards always do best by joining the largest group. Prefer the
+        // strongest card on ties so the substitution is deterministic
+        counts.sort_by(|a, b| {
+            b.0.cmp(&a.0)
+                .then_with(|| self.rank(b.1).cmp(&self.rank(a.1)))
+        });
+        let substitution = match counts.first_mut() {
+            _ if wilds == 0 => None,
+            Some(first) => {
+                first.0 += wilds;
+                Some(first.1)
+            }
+            None => {
+                let best = *self
+                    .order
+                    .iter()
+                    .rev()
+                    .find(|c| !self.wild.is_wild(**c))
+                    .unwrap();
+                counts.push((wilds, best));
+                Some(best)
+            }
+        };
+
+        let hand_type = match (counts[0].0, counts.get(1).map_or(0, |c| c.0)) {
+            (5, _) => HandType::FiveOfAKind,
+            (4, _) => HandType::FourOfAKind,
+            (3, 2) => HandType::FullHouse,
+            (3, _) => HandType::ThreeOfAKind,
+            (2, 2) => HandType::TwoPair,
+            (2, _) => HandType::OnePair,
+            _ => HandType::HighCard,
         };
+        (hand_type, substitution)
     }
-}
 
-fn unique_and_dupes<T: Eq + Clone>(cards: &[T]) -> (Vec<T>, Vec<T>) {
-    let mut unique = Vec::new();
-    let mut seen = Vec::new();
-    for card in cards {
-        if !unique.contains(card) {
-            unique.push(card.clone());
-        } else {
-            seen.push(card.clone());
+    fn hand(&self, s: &str) -> Hand {
+        let (labels, bid) = s.split_once(' ').unwrap();
+        let (hand_type, substitution) = self.classify(labels);
+        Hand {
+            labels: labels.to_string(),
+            ranks: labels.chars().map(|c| self.rank(c)).collect(),
+            bid: bid.parse().unwrap(),
+            hand_type,
+            substitution,
         }
     }
-    (unique, seen)
 }
 
-impl Hand<CardPartOne> {
-    fn set_custom_hand(&mut self) {
-        let mut sorted_cards = self.cards;
-        sorted_cards.sort();
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.hand_type
+            .cmp(&other.hand_type)
+            .then_with(|| self.ranks.cmp(&other.ranks))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        let (dedup, duplicates) = unique_and_dupes(&sorted_cards);
-        self.set_hand(&dedup, &duplicates);
+impl fmt::Display for Hand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:?}", self.labels, self.hand_type)?;
+        if let Some(c) = self.substitution {
+            write!(f, " (wildcards as {})", c)?;
+        }
+        Ok(())
     }
 }
 
-fn task_one(input: &[String]) -> usize {
-    let mut hands: Vec<_> = input
-        .iter()
-        .map(|s| {
-            let (s, bid) = s.split_once(' ').unwrap();
-            let mut cards = [CardPartOne::Two; 5];
-            fill_cards(&mut cards, s);
-            let mut hand = Hand::new(cards, bid.parse::<usize>().unwrap());
-            hand.set_custom_hand();
-            hand
-        })
-        .collect();
+fn total_winnings(input: &[String], rules: &Rules) -> usize {
+    let mut hands: Vec<_> = input.iter().map(|s| rules.hand(s)).collect();
 
     hands.sort();
 
     hands
         .iter()
         .enumerate()
-        .map(|(i, hand)| (i + 1) * hand.bid)
+        .map(|(i, hand)| {
+            if explain() {
+                println!("{}: {}", i + 1, hand);
+            }
+            (i + 1) * hand.bid
+        })
         .sum()
 }
 
-fn task_two(input: &[String]) -> usize {
-    let mut hands: Vec<_> = input
-        .iter()
-        .map(|s| {
-            let (s, bid) = s.split_once(' ').unwrap();
-            let mut cards = [CardPartTwo::Two; 5];
-            fill_cards(&mut cards, s);
-            let mut hand = Hand::new(cards, bid.parse::<usize>().unwrap());
-            hand.set_custom_hand();
-            hand
-        })
-        .collect();
-
-    hands.sort();
+fn task_one(input: &[String]) -> Answer {
+    total_winnings(input, &Rules::new("23456789TJQKA", WildPolicy::none())).into()
+}
 
-    hands
-        .iter()
-        .enumerate()
-        .map(|(i, hand)| (i + 1) * hand.bid)
-        .sum()
+fn task_two(input: &[String]) -> Answer {
+    total_winnings(
+        input,
+        &Rules::new(
+            "23456789TJQKA",
+            get_wild_policy().unwrap_or_else(WildPolicy::jokers_low),
+        ),
+    )
+    .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -271,26 +239,21 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed().as_millis();
+fn time(task: Task, input: &[String]) {
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => (
+            format!("{}ms, {}", usage.elapsed.as_millis(), usage.memory()),
+            res.to_string(),
+        ),
+        Err(status) => ("-".to_string(), status),
+    };
 
     match task {
         Task::One => {
-            println!("({}ms)\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}ms)\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -300,3 +263,11 @@ fn get_input_file() -> String {
         .nth(1)
         .unwrap_or_else(|| "input".to_string())
 }
+
+fn explain() -> bool {
+    std::env::var("EXPLAIN").is_ok()
+}
+
+fn get_wild_policy() -> Option<WildPolicy> {
+    std::env::var("WILD").ok().map(|w| w.parse().unwrap())
+}
@@ -0,0 +1,34 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// Two lists of `size` location ids, drawn from a narrow range so ids repeat
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let spread = rng.range(1..(size as i64 * 2).max(2));
+    (0..size)
+        .map(|_| {
+            format!(
+                "{}   {}",
+                10000 + rng.range(0..spread),
+                10000 + rng.range(0..spread)
+            )
+        })
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let (mut left, mut right): (Vec<i64>, Vec<i64>) = input
+        .iter()
+        .map(|l| {
+            let (a, b) = l.split_once("   ").unwrap();
+            (a.parse::<i64>().unwrap(), b.parse::<i64>().unwrap())
+        })
+        .unzip();
+    left.sort_unstable();
+    right.sort_unstable();
+    let distance: i64 = left.iter().zip(&right).map(|(a, b)| (a - b).abs()).sum();
+    let similarity: i64 = left
+        .iter()
+        .map(|a| a * right.iter().filter(|b| *b == a).count() as i64)
+        .sum();
+    (distance.into(), similarity.into())
+}
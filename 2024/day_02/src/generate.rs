@@ -0,0 +1,53 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` reports of five to eight levels. Most are safe apart from one or
+/// two bad levels
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let sign = if rng.chance(0.5) { 1 } else { -1 };
+            let mut level = rng.range(20..80);
+            let mut levels = Vec::new();
+            for _ in 0..rng.range(5..9) {
+                levels.push(level);
+                level += sign * rng.range(1..4);
+            }
+            for _ in 0..rng.below(3) {
+                let i = rng.below(levels.len());
+                levels[i] += rng.range(-4..5);
+            }
+            levels
+                .iter()
+                .map(i64::to_string)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+fn safe(levels: &[i64]) -> bool {
+    let steps: Vec<i64> = levels.windows(2).map(|w| w[1] - w[0]).collect();
+    steps.iter().all(|s| (1..=3).contains(s)) || steps.iter().all(|s| (-3..=-1).contains(s))
+}
+
+// Tries every way of removing a single level
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let reports: Vec<Vec<i64>> = input
+        .iter()
+        .map(|l| l.split(' ').map(|n| n.parse().unwrap()).collect())
+        .collect();
+    let strict = reports.iter().filter(|r| safe(r)).count();
+    let dampened = reports
+        .iter()
+        .filter(|r| {
+            safe(r)
+                || (0..r.len()).any(|i| {
+                    let mut fewer = r.to_vec();
+                    fewer.remove(i);
+                    safe(&fewer)
+                })
+        })
+        .count();
+    (strict.into(), dampened.into())
+}
@@ -1,61 +1,128 @@
-fn is_valid(chars: &[i32]) -> bool {
-    let num_pos = chars.windows(2).filter(|it| it[1] - it[0] >= 0).count();
-    let all_pos = num_pos >= chars.len() - 2;
-    chars.windows(2).all(|c| {
-        let at_most_three = c[0].abs_diff(c[1]) <= 3;
-        if all_pos {
-            at_most_three && c[1] - c[0] > 0
-        } else {
-            at_most_three && c[0] - c[1] > 0
-        }
-    })
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
+#[derive(Debug, Clone, Copy)]
+struct Rules {
+    min_step: i32,
+    max_step: i32,
+    tolerance: usize,
 }
 
-fn task_one(input: &[String]) -> usize {
-    input
-        .iter()
-        .filter(|it| {
-            let chars: Vec<i32> = it
-                .split_whitespace()
-                .map(|c| c.parse::<i32>().unwrap())
-                .collect();
+impl Rules {
+    fn new(tolerance: usize) -> Self {
+        let env =
+            |key: &str, default: i32| std::env::var(key).map_or(default, |v| v.parse().unwrap());
+        Self {
+            min_step: env("MINSTEP", 1),
+            max_step: env("MAXSTEP", 3),
+            tolerance,
+        }
+    }
 
-            is_valid(&chars)
-        })
-        .count()
+    fn allowed(&self, from: i32, to: i32, sign: i32) -> bool {
+        (self.min_step..=self.max_step).contains(&((to - from) * sign))
+    }
+
+    // Fewest removed levels that make the report safe going in the direction
+    // of `sign`. `best[i][r]` holds the kept level before `i` when `i` is kept
+    // with `r` removals so far, and at most `tolerance` levels can be skipped
+    // between two kept ones, so this is linear in the report length
+    fn repair_in_direction(&self, levels: &[i32], sign: i32) -> Option<Vec<usize>> {
+        let k = self.tolerance;
+        let n = levels.len();
+        let mut best: Vec<Vec<Option<Option<usize>>>> = vec![vec![None; k + 1]; n];
+
+        for i in 0..n {
+            if i <= k {
+                best[i][i] = Some(None);
+            }
+            for skipped in 0..(k + 1).min(i) {
+                let j = i - 1 - skipped;
+                if !self.allowed(levels[j], levels[i], sign) {
+                    continue;
+                }
+                for r in 0..=k - skipped {
+                    if best[j][r].is_some() && best[i][r + skipped].is_none() {
+                        best[i][r + skipped] = Some(Some(j));
+                    }
+                }
+            }
+        }
+
+        let (mut i, mut r) = (0..n)
+            .flat_map(|i| (0..=k).map(move |r| (i, r)))
+            .filter(|(i, r)| best[*i][*r].is_some() && r + (n - 1 - i) <= k)
+            .min_by_key(|(i, r)| r + (n - 1 - i))?;
+
+        let mut kept = vec![false; n];
+        loop {
+            kept[i] = true;
+            match best[i][r].unwrap() {
+                Some(j) => {
+                    r -= i - j - 1;
+                    i = j;
+                }
+                None => break,
+            }
+        }
+        Some((0..n).filter(|i| !kept[*i]).collect())
+    }
+
+    fn repair(&self, levels: &[i32]) -> Option<Vec<usize>> {
+        [1, -1]
+            .into_iter()
+            .filter_map(|sign| self.repair_in_direction(levels, sign))
+            .min_by_key(|removed| removed.len())
+    }
+}
+
+fn parse(s: &str) -> Vec<i32> {
+    s.split_whitespace()
+        .map(|c| c.parse::<i32>().unwrap())
+        .collect()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn count_safe(input: &[String], rules: Rules) -> usize {
     input
         .iter()
         .filter(|it| {
-            let chars: Vec<i32> = it
-                .split_whitespace()
-                .map(|c| c.parse::<i32>().unwrap())
-                .collect();
-
-            for (i, _) in chars.iter().enumerate() {
-                let mut cloned = chars.clone();
-
-                if is_valid(&cloned) {
-                    return true;
-                } else {
-                    cloned.remove(i);
-                    if is_valid(&cloned) {
-                        return true;
-                    }
+            let levels = parse(it);
+            let repair = rules.repair(&levels);
+            if std::env::var("REPAIRS").is_ok() {
+                match &repair {
+                    Some(removed) if removed.is_empty() => println!("{}: safe", it),
+                    Some(removed) => println!("{}: remove levels {:?}", it, removed),
+                    None => println!("{}: unsafe", it),
                 }
             }
-
-            false
+            repair.is_some()
         })
         .count()
 }
 
+fn task_one(input: &[String]) -> Answer {
+    count_safe(input, Rules::new(0)).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    let tolerance = std::env::var("TOLERANCE").map_or(1, |t| t.parse().unwrap());
+    count_safe(input, Rules::new(tolerance)).into()
+}
+
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -69,35 +136,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,62 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+const NOISE: &str = "mul(),don't()x%&[]! 0123456789";
+
+/// `size` lines of corrupted memory. Valid instructions are mixed with
+/// broken ones and random characters, and can run across line ends
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let noise: Vec<char> = NOISE.chars().collect();
+    (0..size.max(1))
+        .map(|_| {
+            let mut line = String::new();
+            for _ in 0..rng.range(5..20) {
+                match rng.below(8) {
+                    0..=2 => {
+                        let (a, b) = (rng.range(0..1000), rng.range(0..1000));
+                        line.push_str(&format!("mul({},{})", a, b));
+                    }
+                    3 => line.push_str(&format!(
+                        "mul({},{}",
+                        rng.range(0..10000),
+                        rng.range(0..100)
+                    )),
+                    4 => line.push_str(&format!("mul({}, {})", rng.range(0..10), rng.range(0..10))),
+                    5 => line.push_str(if rng.chance(0.5) { "do()" } else { "don't()" }),
+                    _ => line.extend((0..rng.range(1..6)).map(|_| *rng.pick(&noise))),
+                }
+            }
+            line
+        })
+        .collect()
+}
+
+// The numbers of a `mul(a,b)` starting at `at`, if it is well formed
+fn mul_at(memory: &str, at: usize) -> Option<usize> {
+    let rest = memory[at..].strip_prefix("mul(")?;
+    let (args, _) = rest.split_once(')')?;
+    let (a, b) = args.split_once(',')?;
+    let number = |s: &str| {
+        let ok = (1..=3).contains(&s.len()) && s.bytes().all(|c| c.is_ascii_digit());
+        ok.then(|| s.parse::<usize>().unwrap())
+    };
+    Some(number(a)? * number(b)?)
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let memory = input.concat();
+    let (mut all, mut enabled_only, mut enabled) = (0, 0, true);
+    for at in 0..memory.len() {
+        if memory[at..].starts_with("do()") {
+            enabled = true;
+        } else if memory[at..].starts_with("don't()") {
+            enabled = false;
+        } else if let Some(product) = mul_at(&memory, at) {
+            all += product;
+            if enabled {
+                enabled_only += product;
+            }
+        }
+    }
+    (all.into(), enabled_only.into())
+}
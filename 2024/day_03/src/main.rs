@@ -1,49 +1,175 @@
-use regex::Regex;
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
 
-const MUL: &str = r"mul\((?<left>[0-9]{1,3}),(?<right>[0-9]{1,3})\)";
-const DO: &str = r"(?<do>)do\(\)";
-const DONT: &str = r"(?<dont>)don't\(\)";
+mod generate;
 
-fn task_one(input: &[String]) -> usize {
-    let re = Regex::new(MUL).unwrap();
-    let hay = input.iter().flat_map(|s| s.chars()).collect::<String>();
+#[derive(Debug, Clone, Copy)]
+enum Instruction {
+    Mul(usize, usize),
+    Do,
+    Dont,
+}
 
-    re.captures_iter(&hay)
-        .map(|c| c.extract())
-        .map(|(_, [left, right])| left.parse::<usize>().unwrap() + right.parse::<usize>().unwrap())
-        .sum()
+#[derive(Debug, Clone, Copy)]
+struct Widths {
+    min: usize,
+    max: usize,
 }
 
-fn task_two(input: &[String]) -> usize {
-    let hay = input.iter().flat_map(|s| s.chars()).collect::<String>();
+struct Tokenizer<'a> {
+    memory: &'a [u8],
+    pos: usize,
+    widths: Widths,
+}
 
-    let re = Regex::new(&format!("{MUL}|{DO}|{DONT}")).unwrap();
-    let mut enabled = true;
-    let mut sum = 0;
+#[derive(Debug)]
+struct Step {
+    offset: usize,
+    instruction: Instruction,
+    executed: bool,
+}
+
+impl Widths {
+    fn from_env() -> Self {
+        let env =
+            |key: &str, default: usize| std::env::var(key).map_or(default, |v| v.parse().unwrap());
+        Self {
+            min: env("MINDIGITS", 1),
+            max: env("MAXDIGITS", 3),
+        }
+    }
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(memory: &'a str, widths: Widths) -> Self {
+        Self {
+            memory: memory.as_bytes(),
+            pos: 0,
+            widths,
+        }
+    }
 
-    for it in re.captures_iter(&hay) {
-        match it.get(0).unwrap().as_str() {
-            "don't()" => enabled = false,
-            "do()" => enabled = true,
-            s => {
-                let (left, right) = s.split_once(',').unwrap();
-                let left = left.replace("mul(", "").parse::<usize>().unwrap();
-                let right = right.replace(")", "").parse::<usize>().unwrap();
-
-                if enabled {
-                    sum += left * right;
+    fn literal(&self, pos: usize, lit: &str) -> Option<usize> {
+        self.memory[pos..]
+            .starts_with(lit.as_bytes())
+            .then_some(pos + lit.len())
+    }
+
+    fn number(&self, pos: usize) -> Option<(usize, usize)> {
+        let len = self.memory[pos..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if !(self.widths.min..=self.widths.max).contains(&len) {
+            return None;
+        }
+        let num = std::str::from_utf8(&self.memory[pos..pos + len])
+            .unwrap()
+            .parse()
+            .ok()?;
+        Some((num, pos + len))
+    }
+
+    fn mul(&self, pos: usize) -> Option<(Instruction, usize)> {
+        let pos = self.literal(pos, "mul(")?;
+        let (left, pos) = self.number(pos)?;
+        let pos = self.literal(pos, ",")?;
+        let (right, pos) = self.number(pos)?;
+        let pos = self.literal(pos, ")")?;
+        Some((Instruction::Mul(left, right), pos))
+    }
+
+    fn instruction(&self, pos: usize) -> Option<(Instruction, usize)> {
+        self.mul(pos)
+            .or_else(|| Some((Instruction::Do, self.literal(pos, "do()")?)))
+            .or_else(|| Some((Instruction::Dont, self.literal(pos, "don't()")?)))
+    }
+}
+
+// Anything that doesn't parse as an instruction is corrupted and skipped one
+// byte at a time
+impl Iterator for Tokenizer<'_> {
+    type Item = (usize, Instruction);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.memory.len() {
+            let offset = self.pos;
+            match self.instruction(offset) {
+                Some((instruction, end)) => {
+                    self.pos = end;
+                    return Some((offset, instruction));
                 }
+                None => self.pos += 1,
             }
         }
+        None
+    }
+}
+
+fn run(memory: &str, conditionals: bool) -> (usize, Vec<Step>) {
+    let mut enabled = true;
+    let mut sum = 0;
+    let mut trace = Vec::new();
+
+    for (offset, instruction) in Tokenizer::new(memory, Widths::from_env()) {
+        let executed = match instruction {
+            Instruction::Mul(left, right) if enabled => {
+                sum += left * right;
+                true
+            }
+            Instruction::Mul(..) => false,
+            Instruction::Do | Instruction::Dont if !conditionals => false,
+            Instruction::Do => {
+                enabled = true;
+                true
+            }
+            Instruction::Dont => {
+                enabled = false;
+                true
+            }
+        };
+        trace.push(Step {
+            offset,
+            instruction,
+            executed,
+        });
     }
 
+    (sum, trace)
+}
+
+fn execute(input: &[String], conditionals: bool) -> usize {
+    let memory = input.concat();
+    let (sum, trace) = run(&memory, conditionals);
+    if std::env::var("TRACE").is_ok() {
+        for step in trace {
+            let status = if step.executed { "" } else { " (skipped)" };
+            println!("{:>6}: {:?}{}", step.offset, step.instruction, status);
+        }
+    }
     sum
 }
 
+fn task_one(input: &[String]) -> Answer {
+    execute(input, false).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    execute(input, true).into()
+}
+
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -57,35 +183,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
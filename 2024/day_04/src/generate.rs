@@ -0,0 +1,37 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A word search `size` letters on a side, using only the letters of XMAS
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let letters = ['X', 'M', 'A', 'S'];
+    (0..size.max(1))
+        .map(|_| (0..size.max(1)).map(|_| *rng.pick(&letters)).collect())
+        .collect()
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let grid: Vec<Vec<char>> = input.iter().map(|l| l.chars().collect()).collect();
+    let at = |x: i64, y: i64| {
+        let row = grid.get(usize::try_from(y).ok()?)?;
+        row.get(usize::try_from(x).ok()?).copied()
+    };
+    let (mut words, mut crosses) = (0, 0);
+    for y in 0..grid.len() as i64 {
+        for x in 0..grid[0].len() as i64 {
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    let word: Option<String> = (0..4).map(|k| at(x + dx * k, y + dy * k)).collect();
+                    words += (word.as_deref() == Some("XMAS")) as usize;
+                }
+            }
+            let diagonal = |dx: i64| {
+                let word: Option<String> = [at(x - dx, y - 1), at(x, y), at(x + dx, y + 1)]
+                    .into_iter()
+                    .collect();
+                matches!(word.as_deref(), Some("MAS") | Some("SAM"))
+            };
+            crosses += (diagonal(1) && diagonal(-1)) as usize;
+        }
+    }
+    (words.into(), crosses.into())
+}
@@ -43,7 +43,7 @@ impl<T> Matrix<T> {
     pub fn iter(&self) -> MatrixIter<T> {
         MatrixIter {
             matrix: self,
-            curr: (0, 0),
+            curr: (-1, 0),
         }
     }
 
@@ -0,0 +1,61 @@
+use std::cmp::Ordering;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// Ordering rules for every pair of up to twenty pages, then `size` updates
+/// of an odd number of pages, about half of them in order
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut pages: Vec<i64> = (10..100).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate(rng.range(5..21) as usize);
+
+    let mut rules = Vec::new();
+    for (i, a) in pages.iter().enumerate() {
+        for b in &pages[i + 1..] {
+            rules.push(format!("{}|{}", a, b));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let mut lines = rules;
+    lines.push(String::new());
+    for _ in 0..size.max(1) {
+        let mut picked: Vec<usize> = (0..pages.len()).collect();
+        rng.shuffle(&mut picked);
+        let len = (rng.below(pages.len().div_ceil(2)) * 2 + 1).min(pages.len());
+        picked.truncate(len);
+        if rng.chance(0.5) {
+            picked.sort_unstable();
+        }
+        let update: Vec<String> = picked.iter().map(|i| pages[*i].to_string()).collect();
+        lines.push(update.join(","));
+    }
+    lines
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let blank = input.iter().position(|l| l.is_empty()).unwrap();
+    let rules = &input[..blank];
+    let before = |a: &str, b: &str| rules.contains(&format!("{}|{}", a, b));
+
+    let (mut ordered, mut fixed) = (0, 0);
+    for line in &input[blank + 1..] {
+        let mut update: Vec<&str> = line.split(',').collect();
+        let in_order =
+            (0..update.len()).all(|i| (i + 1..update.len()).all(|j| !before(update[j], update[i])));
+        if in_order {
+            ordered += update[update.len() / 2].parse::<usize>().unwrap();
+        } else {
+            update.sort_by(|a, b| {
+                if before(a, b) {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            });
+            fixed += update[update.len() / 2].parse::<usize>().unwrap();
+        }
+    }
+    (ordered.into(), fixed.into())
+}
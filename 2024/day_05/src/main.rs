@@ -1,9 +1,14 @@
 use std::collections::{HashMap, HashSet};
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+
+mod generate;
+
 #[derive(Default)]
 struct Page {
-    before: HashMap<i32, HashSet<i32>>,
-    after: HashMap<i32, HashSet<i32>>,
+    // `(a, b)` means `a` has to be printed before `b`
+    rules: HashSet<(i32, i32)>,
     updates: Vec<Vec<i32>>,
 }
 
@@ -14,10 +19,7 @@ fn parse(input: &[String]) -> Page {
         if let Some((left, right)) = line.split_once('|') {
             let left = left.parse::<i32>().unwrap();
             let right = right.parse::<i32>().unwrap();
-
-            page.before.entry(left).or_default().insert(right);
-
-            page.after.entry(right).or_default().insert(left);
+            page.rules.insert((left, right));
         } else {
             let numbers = line
                 .split(',')
@@ -33,88 +35,112 @@ fn parse(input: &[String]) -> Page {
     page
 }
 
-fn get_updates(page: &Page) -> (Vec<Vec<i32>>, Vec<Vec<i32>>) {
-    let mut valid_updates = vec![];
-    let mut invalid_updates = vec![];
-
-    for update in page.updates.iter() {
-        let mut valid = true;
-        for (i, num) in update.iter().enumerate() {
-            let (before, after) = update.split_at(i);
-            let before_set = HashSet::from_iter(before.iter().map(|it| it.to_owned()));
-            let after_set = HashSet::from_iter(after.iter().map(|it| it.to_owned()));
-
-            if let Some(allowed_before) = page.before.get(num) {
-                if !allowed_before
-                    .intersection(&before_set)
-                    .collect::<Vec<_>>()
-                    .is_empty()
-                {
-                    valid = false;
-                    continue;
-                }
-            }
+impl Page {
+    // The rules that matter for an update, as edges between indices into it
+    fn edges(&self, update: &[i32]) -> Vec<(usize, usize)> {
+        (0..update.len())
+            .flat_map(|i| (0..update.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| self.rules.contains(&(update[*i], update[*j])))
+            .collect()
+    }
 
-            if let Some(allowed_after) = page.after.get(num) {
-                if !allowed_after
-                    .intersection(&after_set)
-                    .collect::<Vec<_>>()
-                    .is_empty()
-                {
-                    valid = false;
-                    continue;
-                }
+    fn violation(&self, update: &[i32]) -> Option<(i32, i32)> {
+        self.edges(update)
+            .into_iter()
+            .find(|(i, j)| i > j)
+            .map(|(i, j)| (update[i], update[j]))
+    }
+
+    // Kahn's algorithm, picking the earliest page in the update on ties so
+    // valid updates come back unchanged. On a cycle, returns the pages in it
+    fn order(&self, update: &[i32]) -> Result<Vec<i32>, Vec<i32>> {
+        let edges = self.edges(update);
+        let mut incoming = vec![0; update.len()];
+        let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, j) in &edges {
+            incoming[*j] += 1;
+            outgoing.entry(*i).or_default().push(*j);
+        }
+
+        let mut ordered = Vec::new();
+        let mut done = vec![false; update.len()];
+        while let Some(next) = (0..update.len()).find(|i| !done[*i] && incoming[*i] == 0) {
+            done[next] = true;
+            ordered.push(update[next]);
+            for j in outgoing.get(&next).into_iter().flatten() {
+                incoming[*j] -= 1;
             }
         }
-        if valid {
-            valid_updates.push(update.to_vec());
-        } else {
-            invalid_updates.push(update.to_vec());
+
+        if ordered.len() == update.len() {
+            return Ok(ordered);
+        }
+
+        // Every page left still has a predecessor that is left, so walking
+        // backwards has to run into a cycle
+        let mut path = vec![(0..update.len()).find(|i| !done[*i]).unwrap()];
+        loop {
+            let last = *path.last().unwrap();
+            let (prev, _) = *edges.iter().find(|(i, j)| *j == last && !done[*i]).unwrap();
+            if let Some(start) = path.iter().position(|p| *p == prev) {
+                return Err(path[start..].iter().rev().map(|i| update[*i]).collect());
+            }
+            path.push(prev);
         }
     }
+}
 
-    (valid_updates, invalid_updates)
+fn middle(update: &[i32]) -> usize {
+    update[(update.len() - 1) / 2] as usize
 }
 
-fn task_one(input: &[String]) -> usize {
+fn task_one(input: &[String]) -> Answer {
     let page = parse(input);
-    let (valid_updates, _) = get_updates(&page);
 
-    valid_updates
+    page.updates
         .iter()
-        .map(|it| it[(it.len() - 1) / 2] as usize)
-        .sum()
+        .filter(|update| page.violation(update).is_none())
+        .map(|update| middle(update))
+        .sum::<usize>()
+        .into()
 }
 
-fn task_two(input: &[String]) -> usize {
+fn task_two(input: &[String]) -> Answer {
     let page = parse(input);
-    let (_, invalid_updates) = get_updates(&page);
-
-    invalid_updates
-        .into_iter()
-        .map(|it| {
-            let mut vec: Vec<i32> = vec![];
-            for num in it.iter() {
-                let empty = HashSet::new();
-                let after = page.after.get(num).unwrap_or(&empty);
-
-                let index = vec
-                    .iter()
-                    .position(|it| after.contains(it))
-                    .unwrap_or(vec.len());
-
-                vec.insert(index, *num);
+    let report = std::env::var("REPORT").is_ok();
+
+    page.updates
+        .iter()
+        .filter_map(|update| {
+            let (before, after) = page.violation(update)?;
+            if report {
+                println!("{:?} breaks {}|{}", update, before, after);
+            }
+            match page.order(update) {
+                Ok(ordered) => Some(ordered),
+                Err(cycle) => {
+                    eprintln!("{:?} has a rule cycle: {:?}", update, cycle);
+                    None
+                }
             }
-            vec
         })
-        .map(|it| it[(it.len() - 1) / 2] as usize)
-        .sum()
+        .map(|update| middle(&update))
+        .sum::<usize>()
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -128,35 +154,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
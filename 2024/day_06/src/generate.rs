@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A lab `size` cells on a side with scattered obstructions and the guard
+/// facing up. Labs the guard never leaves are redrawn
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(1);
+    loop {
+        let density = rng.range(5..25) as f64 / 100.0;
+        let mut grid: Vec<Vec<char>> = (0..size)
+            .map(|_| {
+                (0..size)
+                    .map(|_| if rng.chance(density) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect();
+        grid[rng.below(size)][rng.below(size)] = '^';
+        let lines: Vec<String> = grid.iter().map(|row| row.iter().collect()).collect();
+        if patrol(&grid, None).is_some() {
+            return lines;
+        }
+    }
+}
+
+// The cells the guard visits before leaving, or `None` if she walks in a loop
+fn patrol(grid: &[Vec<char>], extra: Option<(usize, usize)>) -> Option<HashSet<(usize, usize)>> {
+    let y = grid.iter().position(|row| row.contains(&'^')).unwrap();
+    let x = grid[y].iter().position(|c| *c == '^').unwrap();
+    let (mut x, mut y, mut dx, mut dy) = (x as i64, y as i64, 0, -1);
+    let mut states = HashSet::new();
+    let mut visited = HashSet::new();
+    loop {
+        visited.insert((x as usize, y as usize));
+        if !states.insert((x, y, dx, dy)) {
+            return None;
+        }
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || ny as usize >= grid.len() || nx as usize >= grid[0].len() {
+            return Some(visited);
+        }
+        if grid[ny as usize][nx as usize] == '#' || extra == Some((nx as usize, ny as usize)) {
+            (dx, dy) = (-dy, dx);
+        } else {
+            (x, y) = (nx, ny);
+        }
+    }
+}
+
+// Tries an obstruction on every open cell
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let grid: Vec<Vec<char>> = input.iter().map(|l| l.chars().collect()).collect();
+    let visited = patrol(&grid, None).unwrap().len();
+    let mut loops = 0;
+    for y in 0..grid.len() {
+        for x in 0..grid[0].len() {
+            if grid[y][x] == '.' && patrol(&grid, Some((x, y))).is_none() {
+                loops += 1;
+            }
+        }
+    }
+    (visited.into(), loops.into())
+}
@@ -1,59 +1,170 @@
 use std::collections::HashSet;
 
-use matrix::{Direction, Matrix};
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
+use matrix::Matrix;
 
+mod generate;
+#[allow(dead_code)]
 mod matrix;
 
-fn walk(
-    matrix: &Matrix<u8>,
-    visited: &mut HashSet<(usize, usize)>,
-    curr: (i32, i32),
-    dir: Direction,
-) {
-    if !matrix.in_grid(curr) {
-        return;
-    }
+// Up, right, down, left. Turning right is the next one in the list
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+struct Lab {
+    width: usize,
+    height: usize,
+    obstacles: Vec<bool>,
+    start: usize,
+    // Where the guard ends up facing an obstacle when walking from a cell in
+    // each direction, or `None` if she walks out of the lab
+    jumps: [Vec<Option<usize>>; 4],
+}
 
-    visited.insert((curr.0 as usize, curr.1 as usize));
+enum Outcome {
+    Exits(HashSet<usize>),
+    Loops,
+}
 
-    if matrix.get(dir.to_index(curr)) == Some(&b'#') {
-        let new_dir = match dir {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-            _ => unreachable!(),
+impl Lab {
+    fn new(matrix: &Matrix<u8>) -> Self {
+        let (width, height) = (matrix.width(), matrix.height());
+        let cells: Vec<_> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| matrix[(x, y)]))
+            .collect();
+        let mut lab = Self {
+            width,
+            height,
+            obstacles: cells.iter().map(|c| *c == b'#').collect(),
+            start: cells.iter().position(|c| *c == b'^').unwrap(),
+            jumps: Default::default(),
         };
+        lab.jumps = std::array::from_fn(|dir| lab.build_jumps(dir));
+        lab
+    }
 
-        walk(matrix, visited, new_dir.to_index(curr), new_dir);
-    } else {
-        walk(matrix, visited, dir.to_index(curr), dir);
+    fn step(&self, cell: usize, dir: usize) -> Option<usize> {
+        let (dx, dy) = DIRECTIONS[dir];
+        let x = (cell % self.width) as i32 + dx;
+        let y = (cell / self.width) as i32 + dy;
+        let in_lab = (0..self.width as i32).contains(&x) && (0..self.height as i32).contains(&y);
+        in_lab.then(|| y as usize * self.width + x as usize)
+    }
+
+    // Filled in starting from the edge the guard walks towards, so the next
+    // cell is always resolved before the current one
+    fn build_jumps(&self, dir: usize) -> Vec<Option<usize>> {
+        let mut jumps = vec![None; self.obstacles.len()];
+        let mut cells: Vec<_> = (0..self.obstacles.len()).collect();
+        if dir == 1 || dir == 2 {
+            cells.reverse();
+        }
+        for cell in cells {
+            jumps[cell] = match self.step(cell, dir) {
+                None => None,
+                Some(next) if self.obstacles[next] => Some(cell),
+                Some(next) => jumps[next],
+            };
+        }
+        jumps
     }
-}
 
-fn task_one(input: &[String]) -> usize {
-    let matrix = Matrix::from(input);
-    let (starting_pos, _) = matrix.iter().find(|c| *c.1 == b'^').unwrap();
-    let mut visited = HashSet::from([starting_pos]);
+    // Steps from `from` to `to` when `to` lies straight ahead in `dir`
+    fn distance(&self, from: usize, to: usize, dir: usize) -> Option<usize> {
+        let (fx, fy) = ((from % self.width) as i32, (from / self.width) as i32);
+        let (tx, ty) = ((to % self.width) as i32, (to / self.width) as i32);
+        let (dx, dy) = DIRECTIONS[dir];
+        let k = (tx - fx) * dx + (ty - fy) * dy;
+        (k > 0 && (fx + dx * k, fy + dy * k) == (tx, ty)).then_some(k as usize)
+    }
+
+    fn walk(&self) -> Outcome {
+        let mut visited = HashSet::from([self.start]);
+        let mut seen = vec![0u8; self.obstacles.len()];
+        let (mut cell, mut dir) = (self.start, 0);
+        loop {
+            if seen[cell] & (1 << dir) != 0 {
+                return Outcome::Loops;
+            }
+            seen[cell] |= 1 << dir;
+            match self.step(cell, dir) {
+                None => return Outcome::Exits(visited),
+                Some(next) if self.obstacles[next] => dir = (dir + 1) % 4,
+                Some(next) => {
+                    visited.insert(next);
+                    cell = next;
+                }
+            }
+        }
+    }
 
-    walk(
-        &matrix,
-        &mut visited,
-        (starting_pos.0 as i32, starting_pos.1 as i32),
-        Direction::Up,
-    );
+    // Same as `walk` but only visits the cells where the guard turns, using the
+    // jump table. The extra obstruction is checked separately since it is not
+    // part of the table
+    fn loops_with(&self, obstruction: usize) -> bool {
+        let mut seen = vec![0u8; self.obstacles.len()];
+        let (mut cell, mut dir) = (self.start, 0);
+        loop {
+            if seen[cell] & (1 << dir) != 0 {
+                return true;
+            }
+            seen[cell] |= 1 << dir;
+
+            let stop = self.jumps[dir][cell];
+            let until_stop = stop.map_or(usize::MAX, |stop| {
+                if stop == cell {
+                    0
+                } else {
+                    self.distance(cell, stop, dir).unwrap()
+                }
+            });
+            match self.distance(cell, obstruction, dir) {
+                Some(k) if k <= until_stop => {
+                    cell = (1..k).fold(cell, |c, _| self.step(c, dir).unwrap());
+                }
+                _ => match stop {
+                    Some(stop) => cell = stop,
+                    None => return false,
+                },
+            }
+            dir = (dir + 1) % 4;
+        }
+    }
+}
 
-    visited.len()
+fn task_one(input: &[String]) -> Answer {
+    let lab = Lab::new(&Matrix::from(input));
+    match lab.walk() {
+        Outcome::Exits(visited) => visited.len().into(),
+        Outcome::Loops => unreachable!(),
+    }
 }
 
-fn task_two(input: &[String]) -> usize {
-    unimplemented!()
+// Only cells on the original path can change anything
+fn task_two(input: &[String]) -> Answer {
+    let lab = Lab::new(&Matrix::from(input));
+    let Outcome::Exits(visited) = lab.walk() else {
+        unreachable!()
+    };
+    visited
+        .into_iter()
+        .filter(|cell| *cell != lab.start && lab.loops_with(*cell))
+        .count()
+        .into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -67,35 +178,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
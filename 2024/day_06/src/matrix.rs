@@ -40,10 +40,10 @@ impl<T> Matrix<T> {
         Matrix { dim, vec }
     }
 
-    pub fn iter(&self) -> MatrixIter<T> {
+    pub fn iter(&self) -> MatrixIter<'_, T> {
         MatrixIter {
             matrix: self,
-            curr: (0, 0),
+            curr: (-1, 0),
         }
     }
 
@@ -79,9 +79,9 @@ impl<T> Matrix<T> {
 
     pub fn in_grid(&self, idx: (i32, i32)) -> bool {
         idx.0 >= 0
-            && (idx.0 as usize) < self.height()
+            && (idx.0 as usize) < self.width()
             && idx.1 >= 0
-            && (idx.1 as usize) < self.width()
+            && (idx.1 as usize) < self.height()
     }
 }
 
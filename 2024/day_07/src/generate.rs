@@ -0,0 +1,60 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// `size` equations of two to six numbers, zero included. Most test values come
+/// from some choice of operators, the rest are random
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let nums: Vec<u64> = (0..rng.range(2..7))
+                .map(|_| rng.range(0..20) as u64)
+                .collect();
+            let test = if rng.chance(0.7) {
+                nums[1..]
+                    .iter()
+                    .fold(nums[0], |acc, n| apply(rng.below(3), acc, *n))
+            } else {
+                rng.range(1..100_000) as u64
+            };
+            let nums: Vec<String> = nums.iter().map(u64::to_string).collect();
+            format!("{}: {}", test, nums.join(" "))
+        })
+        .collect()
+}
+
+fn apply(op: usize, a: u64, b: u64) -> u64 {
+    match op {
+        0 => a + b,
+        1 => a * b,
+        _ => format!("{}{}", a, b).parse().unwrap(),
+    }
+}
+
+// Counts through every choice of operators, left to right
+fn solvable(test: u64, nums: &[u64], operators: usize) -> bool {
+    let choices = operators.pow(nums.len() as u32 - 1);
+    (0..choices).any(|mut choice| {
+        let value = nums[1..].iter().fold(nums[0], |acc, n| {
+            let op = choice % operators;
+            choice /= operators;
+            apply(op, acc, *n)
+        });
+        value == test
+    })
+}
+
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let (mut two, mut three) = (0, 0);
+    for line in input {
+        let (test, nums) = line.split_once(": ").unwrap();
+        let test: u64 = test.parse().unwrap();
+        let nums: Vec<u64> = nums.split(' ').map(|n| n.parse().unwrap()).collect();
+        if solvable(test, &nums, 2) {
+            two += test;
+        }
+        if solvable(test, &nums, 3) {
+            three += test;
+        }
+    }
+    (two.into(), three.into())
+}
@@ -1,89 +1,168 @@
-fn solve_part1(nums: &[usize], target: usize, current: usize) -> bool {
-    if current > target {
-        return false;
-    }
-
-    if nums.is_empty() {
-        return current == target;
-    }
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
 
-    let (next_num, remaining_nums) = nums.split_at(1);
-    if solve_part1(remaining_nums, target, current + next_num[0]) {
-        return true;
-    }
-    if solve_part1(remaining_nums, target, current * next_num[0]) {
-        return true;
-    }
+mod generate;
 
-    false
+#[derive(Debug, Clone, Copy)]
+enum Operator {
+    Add,
+    Mul,
+    Concat,
 }
 
-fn task_one(input: &[String]) -> usize {
-    input
-        .iter()
-        .filter_map(|it| {
-            let (test, rest) = it.split_once(':').unwrap();
-            let test = test.parse::<usize>().unwrap();
-            let rest = rest
-                .split_whitespace()
-                .map(|n| n.parse::<usize>().unwrap())
-                .collect::<Vec<_>>();
-
-            solve_part1(&rest[1..], test, rest[0]).then_some(test)
-        })
-        .sum()
+struct Equation {
+    test: usize,
+    nums: Vec<usize>,
 }
 
-fn solve_part2(nums: &[usize], target: usize, current: usize) -> bool {
-    if current > target {
-        return false;
+impl Operator {
+    // The value before applying the operator with `n`, if `result` could have
+    // come from it at all
+    fn invert(&self, result: usize, n: usize) -> Option<usize> {
+        match self {
+            Operator::Add => result.checked_sub(n),
+            Operator::Mul => (n != 0 && result.is_multiple_of(n)).then(|| result / n),
+            Operator::Concat => {
+                let pow = 10_usize.pow(n.checked_ilog10().unwrap_or(0) + 1);
+                (result % pow == n).then(|| result / pow)
+            }
+        }
     }
 
-    if nums.is_empty() {
-        return current == target;
+    fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Add => "+",
+            Operator::Mul => "*",
+            Operator::Concat => "||",
+        }
     }
+}
 
-    let (next_num, remaining_nums) = nums.split_at(1);
+impl Equation {
+    fn parse(s: &str) -> Self {
+        let (test, rest) = s.split_once(':').unwrap();
+        Self {
+            test: test.parse().unwrap(),
+            nums: rest
+                .split_whitespace()
+                .map(|n| n.parse().unwrap())
+                .collect(),
+        }
+    }
 
-    if solve_part2(
-        remaining_nums,
-        target,
-        format!("{}{}", current, next_num[0]).parse().unwrap(),
+    // Works from the last number backwards, so most branches are cut as soon as
+    // an operator can't be undone. `ops` is filled in reverse
+    fn search(
+        &self,
+        operators: &[Operator],
+        len: usize,
+        result: usize,
+        ops: &mut Vec<Operator>,
+        found: &mut Vec<Vec<Operator>>,
     ) {
-        return true;
+        if len == 1 {
+            if result == self.nums[0] {
+                found.push(ops.iter().rev().copied().collect());
+            }
+            return;
+        }
+        for op in operators {
+            let n = self.nums[len - 1];
+            if let (Operator::Mul, 0, 0) = (op, n, result) {
+                // Anything times zero is zero, so whatever comes before works
+                ops.push(*op);
+                self.every_prefix(operators, len - 1, ops, found);
+                ops.pop();
+            } else if let Some(prev) = op.invert(result, n) {
+                ops.push(*op);
+                self.search(operators, len - 1, prev, ops, found);
+                ops.pop();
+            }
+        }
     }
 
-    if solve_part2(remaining_nums, target, current + next_num[0]) {
-        return true;
+    // Every choice of operators for the first `len` numbers
+    fn every_prefix(
+        &self,
+        operators: &[Operator],
+        len: usize,
+        ops: &mut Vec<Operator>,
+        found: &mut Vec<Vec<Operator>>,
+    ) {
+        if len == 1 {
+            found.push(ops.iter().rev().copied().collect());
+            return;
+        }
+        for op in operators {
+            ops.push(*op);
+            self.every_prefix(operators, len - 1, ops, found);
+            ops.pop();
+        }
     }
 
-    if solve_part2(remaining_nums, target, current * next_num[0]) {
-        return true;
+    fn solutions(&self, operators: &[Operator]) -> Vec<Vec<Operator>> {
+        let mut found = Vec::new();
+        self.search(
+            operators,
+            self.nums.len(),
+            self.test,
+            &mut Vec::new(),
+            &mut found,
+        );
+        found
     }
 
-    false
+    fn expression(&self, ops: &[Operator]) -> String {
+        let rest = ops
+            .iter()
+            .zip(&self.nums[1..])
+            .map(|(op, n)| format!(" {} {}", op.symbol(), n))
+            .collect::<String>();
+        format!("{}{} = {}", self.nums[0], rest, self.test)
+    }
 }
 
-fn task_two(input: &[String]) -> usize {
+fn calibrate(input: &[String], operators: &[Operator]) -> usize {
     input
         .iter()
-        .filter_map(|it| {
-            let (test, rest) = it.split_once(':').unwrap();
-            let test = test.parse::<usize>().unwrap();
-            let rest = rest
-                .split_whitespace()
-                .map(|n| n.parse::<usize>().unwrap())
-                .collect::<Vec<_>>();
-
-            solve_part2(&rest[1..], test, rest[0]).then_some(test)
+        .map(|it| Equation::parse(it))
+        .filter(|equation| {
+            let solutions = equation.solutions(operators);
+            if std::env::var("EXPRESSIONS").is_ok() {
+                if let Some(ops) = solutions.first() {
+                    println!(
+                        "{} [solutions: {}]",
+                        equation.expression(ops),
+                        solutions.len()
+                    );
+                }
+            }
+            !solutions.is_empty()
         })
+        .map(|equation| equation.test)
         .sum()
 }
 
+fn task_one(input: &[String]) -> Answer {
+    calibrate(input, &[Operator::Add, Operator::Mul]).into()
+}
+
+fn task_two(input: &[String]) -> Answer {
+    calibrate(input, &[Operator::Add, Operator::Mul, Operator::Concat]).into()
+}
+
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -97,35 +176,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
@@ -0,0 +1,56 @@
+use aoc::fixture::Rng;
+use aoc::Answer;
+
+/// A map `size` cells on a side with antennas of a few frequencies
+pub fn input(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(1);
+    let frequencies: Vec<char> = "aAz09".chars().collect();
+    let frequencies = &frequencies[..rng.range(1..6) as usize];
+    (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| {
+                    if rng.chance(0.08) {
+                        *rng.pick(frequencies)
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Checks every cell against every pair of antennas sharing a frequency
+pub fn reference(input: &[String]) -> (Answer, Answer) {
+    let grid: Vec<Vec<char>> = input.iter().map(|l| l.chars().collect()).collect();
+    let antennas: Vec<(i64, i64, char)> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, c)| (x as i64, y as i64, *c))
+        })
+        .filter(|(_, _, c)| *c != '.')
+        .collect();
+
+    let (mut exact, mut in_line) = (0, 0);
+    for y in 0..grid.len() as i64 {
+        for x in 0..grid[0].len() as i64 {
+            let pairs = antennas
+                .iter()
+                .flat_map(|a| antennas.iter().map(move |b| (a, b)));
+            let pairs: Vec<_> = pairs.filter(|(a, b)| a != b && a.2 == b.2).collect();
+            let twice = pairs
+                .iter()
+                .any(|(a, b)| (x - a.0, y - a.1) == (2 * (x - b.0), 2 * (y - b.1)));
+            let line = pairs
+                .iter()
+                .any(|(a, b)| (x - a.0) * (b.1 - a.1) == (y - a.1) * (b.0 - a.0));
+            exact += twice as usize;
+            in_line += line as usize;
+        }
+    }
+    (exact.into(), in_line.into())
+}
@@ -1,128 +1,158 @@
 use std::collections::{HashMap, HashSet};
 
+use aoc::runner::{self, Task};
+use aoc::{fixture, Answer};
 use matrix::Matrix;
 
+mod generate;
+#[allow(dead_code)]
 mod matrix;
 
-fn get_antinodes(a: (i32, i32), b: (i32, i32)) -> [(i32, i32); 2] {
-    let (x, y) = (a.0 - b.0, a.1 - b.1);
-    [(a.0 + x, a.1 + y), (b.0 - x, b.1 - y)]
+#[derive(Debug, Clone, Copy)]
+enum Harmonics {
+    // One antenna twice as far away as the other, on the outside of the pair
+    Exact,
+    // Every grid position in line with the pair
+    Lattice,
+    // In line with the pair, but at most this many steps outside of it
+    Max(usize),
 }
 
-fn task_one(input: &[String]) -> usize {
-    let matrix = Matrix::from(input);
-    let mut map: HashMap<&u8, Vec<(i32, i32)>> = HashMap::new();
-    for (pos, it) in matrix.iter() {
-        match it {
-            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => {
-                let pos = (pos.0 as i32, pos.1 as i32);
-                map.entry(it)
-                    .and_modify(|e| e.push(pos))
-                    .or_insert(vec![pos]);
-            }
-            _ => continue,
-        }
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
     }
+}
 
-    let mut set = HashSet::new();
-
-    for values in map.values() {
-        for pos1 in values.iter() {
-            for pos2 in values.iter() {
-                if pos1 == pos2 {
-                    continue;
-                }
-
-                let antinodes = get_antinodes(*pos1, *pos2);
-                if matrix.in_grid(antinodes[0]) {
-                    set.insert(antinodes[0]);
-                }
-
-                if matrix.in_grid(antinodes[1]) {
-                    set.insert(antinodes[1]);
-                }
-            }
+fn get_antennas(matrix: &Matrix<u8>) -> HashMap<u8, Vec<(i32, i32)>> {
+    let mut map: HashMap<u8, Vec<(i32, i32)>> = HashMap::new();
+    for (pos, it) in matrix.iter() {
+        if it.is_ascii_alphanumeric() {
+            map.entry(*it)
+                .or_default()
+                .push((pos.0 as i32, pos.1 as i32));
         }
     }
-
-    set.len()
+    map
 }
 
-fn get_antinodes_2_1(
+// Walk away from `start` in steps of `step` while still in the grid
+fn walk(
     matrix: &Matrix<u8>,
-    set: &mut HashSet<(i32, i32)>,
-    a: (i32, i32),
-    b: (i32, i32),
-) {
-    if !matrix.in_grid(a) {
-        return;
-    }
-
-    set.insert(a);
-
-    let (x, y) = (a.0 - b.0, a.1 - b.1);
-    let new_a = (a.0 + x, a.1 + y);
-
-    get_antinodes_2_1(matrix, set, new_a, a);
+    start: (i32, i32),
+    step: (i32, i32),
+    limit: usize,
+) -> impl Iterator<Item = (i32, i32)> + '_ {
+    (1..=limit)
+        .map(move |k| (start.0 + step.0 * k as i32, start.1 + step.1 * k as i32))
+        .take_while(|pos| matrix.in_grid(*pos))
 }
 
-fn get_antinodes_2_2(
+fn pair_antinodes(
     matrix: &Matrix<u8>,
     set: &mut HashSet<(i32, i32)>,
     a: (i32, i32),
     b: (i32, i32),
+    harmonics: Harmonics,
 ) {
-    if !matrix.in_grid(b) {
-        return;
+    let d = (b.0 - a.0, b.1 - a.1);
+    match harmonics {
+        Harmonics::Exact => {
+            set.extend(walk(matrix, b, d, 1));
+            set.extend(walk(matrix, a, (-d.0, -d.1), 1));
+        }
+        Harmonics::Lattice | Harmonics::Max(_) => {
+            let g = gcd(d.0, d.1);
+            let step = (d.0 / g, d.1 / g);
+            let limit = match harmonics {
+                Harmonics::Max(n) => n,
+                _ => usize::MAX,
+            };
+            // Everything from a up to b, then outwards on both sides
+            set.extend((0..=g).map(|k| (a.0 + step.0 * k, a.1 + step.1 * k)));
+            set.extend(walk(matrix, b, step, limit));
+            set.extend(walk(matrix, a, (-step.0, -step.1), limit));
+        }
     }
-
-    set.insert(b);
-
-    let (x, y) = (a.0 - b.0, a.1 - b.1);
-    let new_b = (b.0 - x, b.1 - y);
-
-    get_antinodes_2_2(matrix, set, b, new_b);
 }
 
-// Basically just need to keep adding antinodes on the map with the
-// same distance as between two frequencies, while it is still in the grid
-fn task_two(input: &[String]) -> usize {
-    let matrix = Matrix::from(input);
-    let mut map: HashMap<&u8, Vec<(i32, i32)>> = HashMap::new();
-    for (pos, it) in matrix.iter() {
-        match it {
-            b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' => {
-                let pos = (pos.0 as i32, pos.1 as i32);
-                map.entry(it)
-                    .and_modify(|e| e.push(pos))
-                    .or_insert(vec![pos]);
+fn antinodes(matrix: &Matrix<u8>, harmonics: Harmonics) -> HashSet<(i32, i32)> {
+    let mut set = HashSet::new();
+    // Unlimited harmonics cover the whole line, so each line is only walked from the
+    // first antenna on it, along the first pair in each direction. Antennas are in
+    // reading order, so a step towards a later one never points upwards.
+    let (width, height) = (matrix.width() as i32, matrix.height() as i32);
+    let mut walked = vec![0; (2 * width * height) as usize];
+    let mut antenna = 0;
+    for (frequency, positions) in get_antennas(matrix) {
+        for (i, a) in positions.iter().enumerate() {
+            antenna += 1;
+            for b in &positions[i + 1..] {
+                if let Harmonics::Lattice = harmonics {
+                    let g = gcd(b.0 - a.0, b.1 - a.1);
+                    let step = ((b.0 - a.0) / g, (b.1 - a.1) / g);
+                    let idx = (step.1 * 2 * width + step.0 + width) as usize;
+                    let first = walked[idx] != antenna;
+                    walked[idx] = antenna;
+                    if !first
+                        || walk(matrix, *a, (-step.0, -step.1), usize::MAX)
+                            .any(|pos| matrix[pos] == frequency)
+                    {
+                        continue;
+                    }
+                }
+                pair_antinodes(matrix, &mut set, *a, *b, harmonics);
             }
-            _ => continue,
         }
     }
 
-    let mut set = HashSet::new();
+    if std::env::var("RENDER").is_ok() {
+        println!("{}", render(matrix, &set));
+    }
+    set
+}
 
-    for values in map.values() {
-        for pos1 in values.iter() {
-            for pos2 in values.iter() {
-                if pos1 == pos2 {
-                    continue;
-                }
+fn render(matrix: &Matrix<u8>, antinodes: &HashSet<(i32, i32)>) -> Matrix<char> {
+    Matrix::new(
+        matrix
+            .iter()
+            .map(|(pos, it)| match it {
+                b'.' if antinodes.contains(&(pos.0 as i32, pos.1 as i32)) => '#',
+                it => *it as char,
+            })
+            .collect(),
+        (matrix.width(), matrix.height()),
+    )
+}
 
-                get_antinodes_2_1(&matrix, &mut set, *pos1, *pos2);
-                get_antinodes_2_2(&matrix, &mut set, *pos1, *pos2);
-            }
-        }
-    }
+fn task_one(input: &[String]) -> Answer {
+    antinodes(&Matrix::from(input), Harmonics::Exact)
+        .len()
+        .into()
+}
 
-    set.len()
+fn task_two(input: &[String]) -> Answer {
+    let harmonics = match std::env::var("HARMONICS") {
+        Ok(n) => Harmonics::Max(n.parse().unwrap()),
+        Err(_) => Harmonics::Lattice,
+    };
+    antinodes(&Matrix::from(input), harmonics).len().into()
 }
 
 fn main() {
-    let input = read_input(get_input_file());
-    time(Task::One, task_one, &input);
-    time(Task::Two, task_two, &input);
+    runner::serve(task_one, task_two);
+    if fixture::run(generate::input, generate::reference) {
+        return;
+    }
+    let path = get_input_file();
+    if std::path::Path::new(&path).is_dir() {
+        return runner::validate(path, env!("CARGO_MANIFEST_DIR"));
+    }
+    let input = read_input(path);
+    time(Task::One, &input);
+    time(Task::Two, &input);
 }
 
 fn read_input<P>(path: P) -> Vec<String>
@@ -136,35 +166,31 @@ where
         .collect()
 }
 
-enum Task {
-    One,
-    Two,
-}
-
-fn time<F, T, U>(task: Task, f: F, arg: T)
-where
-    F: Fn(T) -> U,
-    U: std::fmt::Display,
-{
-    let t = std::time::Instant::now();
-    let res = f(arg);
-    let elapsed = t.elapsed();
+fn time(task: Task, input: &[String]) {
     let fmt = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
-
-    let (u, elapsed) = match fmt.as_str() {
-        "ms" => ("ms", elapsed.as_millis()),
-        "ns" => ("ns", elapsed.as_nanos()),
-        "us" => ("μs", elapsed.as_micros()),
-        "s" => ("s", elapsed.as_secs() as u128),
-        _ => panic!("unsupported time format"),
+    let (usage, res) = match runner::part(input, task) {
+        Ok((res, usage)) => {
+            let (u, elapsed) = match fmt.as_str() {
+                "ms" => ("ms", usage.elapsed.as_millis()),
+                "ns" => ("ns", usage.elapsed.as_nanos()),
+                "us" => ("μs", usage.elapsed.as_micros()),
+                "s" => ("s", usage.elapsed.as_secs() as u128),
+                _ => panic!("unsupported time format"),
+            };
+            (
+                format!("{}{u}, {}", elapsed, usage.memory()),
+                res.to_string(),
+            )
+        }
+        Err(status) => ("-".to_string(), status),
     };
 
     match task {
         Task::One => {
-            println!("({}{u})\tTask one: \x1b[0;34;34m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask one: \x1b[0;34;34m{}\x1b[0m", usage, res);
         }
         Task::Two => {
-            println!("({}{u})\tTask two: \x1b[0;33;10m{}\x1b[0m", elapsed, res);
+            println!("({})\tTask two: \x1b[0;33;10m{}\x1b[0m", usage, res);
         }
     };
 }
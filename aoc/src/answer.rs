@@ -0,0 +1,195 @@
+use std::fmt;
+
+use num_bigint::{BigInt, BigUint};
+
+// Letters of the 4x6 font used by the puzzles that draw their answer. Every
+// glyph is followed by one blank column
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 6;
+const FONT: [(char, &str); 18] = [
+    ('A', ".##.#..##..######..##..#"),
+    ('B', "###.#..####.#..##..####."),
+    ('C', ".##.#..##...#...#..#.##."),
+    ('E', "#####...###.#...#...####"),
+    ('F', "#####...###.#...#...#..."),
+    ('G', ".##.#..##...#.###..#.###"),
+    ('H', "#..##..######..##..##..#"),
+    ('I', ".###..#...#...#...#..###"),
+    ('J', "..##...#...#...##..#.##."),
+    ('K', "#..##.#.##..#.#.#.#.#..#"),
+    ('L', "#...#...#...#...#...####"),
+    ('O', ".##.#..##..##..##..#.##."),
+    ('P', "###.#..##..####.#...#..."),
+    ('R', "###.#..##..####.#.#.#..#"),
+    ('S', ".####...#....##....####."),
+    ('U', "#..##..##..##..##..#.##."),
+    ('Z', "####...#..#..#..#...####"),
+    (' ', "........................"),
+];
+
+/// The result of one part of a puzzle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    // Only used for integers that do not fit in an i64, so equal numbers
+    // always compare equal
+    Big(BigInt),
+    Text(String),
+    // Rows of a rendered grid, with lit cells as '#'
+    Glyphs(Vec<String>),
+    Unsolved,
+}
+
+impl Answer {
+    pub fn integer<N: Into<BigInt>>(n: N) -> Self {
+        let n = n.into();
+        match i64::try_from(&n) {
+            Ok(n) => Answer::Int(n),
+            Err(_) => Answer::Big(n),
+        }
+    }
+
+    pub fn glyphs<I, S>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Answer::Glyphs(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// Reads the letters drawn by a glyph grid, if every one is known
+    pub fn ocr(&self) -> Option<String> {
+        let Answer::Glyphs(rows) = self else {
+            return None;
+        };
+        if rows.len() != GLYPH_HEIGHT {
+            return None;
+        }
+        let width = rows.iter().map(|row| row.chars().count()).max()?;
+        let lit = |row: &str, col: usize| row.chars().nth(col) == Some('#');
+
+        (0..width.div_ceil(GLYPH_WIDTH))
+            .map(|glyph| {
+                let cells: String = rows
+                    .iter()
+                    .flat_map(|row| {
+                        (0..GLYPH_WIDTH - 1).map(move |dx| {
+                            if lit(row, glyph * GLYPH_WIDTH + dx) {
+                                '#'
+                            } else {
+                                '.'
+                            }
+                        })
+                    })
+                    .collect();
+                FONT.iter().find(|(_, g)| *g == cells).map(|(c, _)| *c)
+            })
+            .collect::<Option<String>>()
+            .map(|text| text.trim_end().to_string())
+    }
+
+    /// Whether this answer is the same as one recorded earlier as text
+    pub fn matches(&self, recorded: &str) -> bool {
+        match self {
+            Answer::Unsolved => false,
+            answer => answer.to_string().trim() == recorded.trim(),
+        }
+    }
+
+    // A kind on the first line and the value on the lines after it, for
+    // passing an answer from the process that solved it
+    pub(crate) fn encode(&self) -> String {
+        match self {
+            Answer::Int(n) => format!("int\n{}", n),
+            Answer::Big(n) => format!("big\n{}", n),
+            Answer::Text(s) => format!("text\n{}", s),
+            Answer::Glyphs(rows) => format!("glyphs\n{}", rows.join("\n")),
+            Answer::Unsolved => "unsolved\n".to_string(),
+        }
+    }
+
+    pub(crate) fn decode(s: &str) -> Option<Self> {
+        let (kind, value) = s.split_once('\n')?;
+        match kind {
+            "int" => value.parse().ok().map(Answer::Int),
+            "big" => value.parse().ok().map(Answer::Big),
+            "text" => Some(Answer::Text(value.to_string())),
+            "glyphs" => Some(Answer::glyphs(value.split('\n'))),
+            "unsolved" => Some(Answer::Unsolved),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Int(n) => n.to_string(),
+            Answer::Big(n) => n.to_string(),
+            Answer::Text(s) => json_string(s),
+            Answer::Glyphs(rows) => format!(
+                "{{\"text\":{},\"rows\":[{}]}}",
+                self.ocr().map_or("null".to_string(), |s| json_string(&s)),
+                rows.iter()
+                    .map(|r| json_string(r))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+            Answer::Unsolved => "null".to_string(),
+        }
+    }
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Big(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Glyphs(rows) => match self.ocr() {
+                Some(text) => write!(f, "{}", text),
+                None => rows.iter().try_for_each(|row| write!(f, "\n{}", row)),
+            },
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::integer(n)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, BigInt, BigUint);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
@@ -0,0 +1,101 @@
+use crate::answer::Answer;
+use crate::runner::{self, Outcome, Task};
+
+const DEFAULT_SIZE: usize = 10;
+
+/// A splitmix64 generator, so an input only depends on its seed
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `range`, which must not be empty
+    pub fn range(&mut self, range: std::ops::Range<i64>) -> i64 {
+        let span = range.end.abs_diff(range.start);
+        range.start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64) < p * (1u64 << 53) as f64
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name).map_or(default, |v| {
+        v.parse()
+            .unwrap_or_else(|_| panic!("{} must be a number", name))
+    })
+}
+
+/// Handles the fixture modes and returns whether one of them ran.
+/// `GENERATE=<seed>` prints an input of size `SIZE` for the seed, and
+/// `STRESS=<count>` checks both parts against the reference solver on `count`
+/// generated inputs, starting from seed `SEED`
+pub fn run<G, R>(generate: G, reference: R) -> bool
+where
+    G: Fn(&mut Rng, usize) -> Vec<String>,
+    R: Fn(&[String]) -> (Answer, Answer),
+{
+    let size = env_or("SIZE", DEFAULT_SIZE);
+    if let Ok(seed) = std::env::var("GENERATE") {
+        for line in generate(&mut Rng::new(seed.parse().unwrap()), size) {
+            println!("{}", line);
+        }
+        return true;
+    }
+    let Ok(count) = std::env::var("STRESS") else {
+        return false;
+    };
+    let count: u64 = count.parse().unwrap();
+    let first = env_or("SEED", 0);
+    let timeout = runner::timeout();
+
+    let mut failures = 0;
+    for seed in first..first + count {
+        let input = generate(&mut Rng::new(seed), size);
+        let (expected_one, expected_two) = reference(&input);
+        for (task, expected) in [(Task::One, expected_one), (Task::Two, expected_two)] {
+            let problem = match runner::run(&input, task, timeout) {
+                Outcome::Solved(answer, _) if answer == expected => continue,
+                Outcome::Solved(answer, _) => {
+                    format!("{} but the reference gives {}", answer, expected)
+                }
+                Outcome::Panicked(msg) => format!("failed: {}", msg),
+                Outcome::Crashed(reason) => format!("crashed: {}", reason),
+                Outcome::TimedOut => format!("timed out after {:?}", timeout),
+            };
+            failures += 1;
+            println!("seed {}\tTask {}: {}", seed, task.name(), problem);
+        }
+    }
+
+    println!("{} of {} results failed", failures, count * 2);
+    if failures > 0 {
+        std::process::exit(1);
+    }
+    true
+}
@@ -0,0 +1,9 @@
+//! What every day shares: the `Answer` a part returns, the runner that
+//! times, isolates and validates the parts, and the fixture that stress tests
+//! them against generated inputs
+
+pub mod answer;
+pub mod fixture;
+pub mod runner;
+
+pub use answer::Answer;
@@ -0,0 +1,398 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::answer::{json_string, Answer};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_POLL: Duration = Duration::from_millis(10);
+
+// Set for the process that `run` starts to solve a part, which writes how it
+// went to the file in `RESULT_VAR`
+const PART_VAR: &str = "AOC_PART";
+const RESULT_VAR: &str = "AOC_RESULT";
+
+// The messages of `unimplemented!()` and `todo!()`
+const UNSOLVED_PANICS: [&str; 2] = ["not implemented", "not yet implemented"];
+
+/// One part of a day
+pub type Solver = fn(&[String]) -> Answer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Task {
+    One,
+    Two,
+}
+
+impl Task {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Task::One => "one",
+            Task::Two => "two",
+        }
+    }
+}
+
+pub enum Outcome {
+    Solved(Answer, Usage),
+    Panicked(String),
+    /// The process died without reporting back, like on a stack overflow
+    Crashed(String),
+    TimedOut,
+}
+
+/// What a part took to solve. The peak only counts memory allocated on top
+/// of what was in use when the part started
+pub struct Usage {
+    pub elapsed: Duration,
+    pub peak: usize,
+}
+
+impl Usage {
+    pub fn memory(&self) -> String {
+        let units = ["B", "KiB", "MiB", "GiB"];
+        let mut size = self.peak as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < units.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        match unit {
+            0 => format!("{}B", self.peak),
+            _ => format!("{:.1}{}", size, units[unit]),
+        }
+    }
+}
+
+// Keeps count of the bytes in use and the most in use at once. Every part is
+// solved in a process of its own, so the peak is that part's alone
+struct CountingAllocator;
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+impl CountingAllocator {
+    fn grow(size: usize) {
+        let in_use = IN_USE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(in_use, Ordering::Relaxed);
+    }
+
+    fn shrink(size: usize) {
+        IN_USE.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::shrink(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            Self::grow(new_size);
+            Self::shrink(layout.size());
+        }
+        new
+    }
+}
+
+/// Runs both parts against every `<name>.txt` in a directory of inputs and
+/// compares with the answers recorded in `<name>.answers`, one line per part.
+/// The directory may also be a root laid out as `<year>/<day>/`, in which
+/// case the inputs for the day whose crate is at `manifest_dir` are picked
+/// from it. With `JSON` set every result is printed as a JSON object instead
+pub fn validate(path: String, manifest_dir: &str) {
+    let timeout = timeout();
+    let json = std::env::var("JSON").is_ok();
+    let dir = day_dir(Path::new(&path), Path::new(manifest_dir));
+
+    let mut inputs: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+        .collect();
+    inputs.sort();
+    if inputs.is_empty() {
+        println!("No inputs in {}", dir.display());
+        return;
+    }
+
+    let mut failures = 0;
+    for input_path in &inputs {
+        let name = input_path.file_stem().unwrap().to_string_lossy();
+        let input = read_input(input_path);
+        let recorded: Vec<String> = std::fs::read_to_string(input_path.with_extension("answers"))
+            .map(|s| s.lines().map(String::from).collect())
+            .unwrap_or_default();
+
+        for (idx, task) in [Task::One, Task::Two].into_iter().enumerate() {
+            let outcome = run(&input, task, timeout);
+            let expected = recorded.get(idx).filter(|r| !r.trim().is_empty());
+            if json {
+                println!(
+                    "{}",
+                    report_json(&name, task.name(), &outcome, expected, timeout)
+                );
+            }
+            let (line, failed) = report(outcome, expected, timeout);
+            failures += failed as usize;
+            if !json {
+                println!("{}\tTask {}: {}", name, task.name(), line);
+            }
+        }
+    }
+
+    if !json {
+        println!("{} of {} results failed", failures, inputs.len() * 2);
+    }
+    if failures > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// How long a part may run, from `TIMEOUT` in seconds
+pub fn timeout() -> Duration {
+    std::env::var("TIMEOUT")
+        .map(|t| Duration::from_secs_f64(t.parse().unwrap()))
+        .unwrap_or(DEFAULT_TIMEOUT)
+}
+
+fn read_input(path: &Path) -> Vec<String> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+fn day_dir(root: &Path, manifest: &Path) -> PathBuf {
+    let day = manifest
+        .file_name()
+        .and_then(|d| d.to_str())
+        .and_then(|d| d.strip_prefix("day_"));
+    let year = manifest.parent().and_then(|y| y.file_name());
+    match (year, day) {
+        (Some(year), Some(day)) if root.join(year).join(day).is_dir() => root.join(year).join(day),
+        _ => root.to_path_buf(),
+    }
+}
+
+/// Solves the part this process was started for by `run`, reports back and
+/// exits. In any other process it returns straight away, so it belongs first
+/// thing in `main`
+pub fn serve(one: Solver, two: Solver) {
+    let Ok(part) = std::env::var(PART_VAR) else {
+        return;
+    };
+    let solver = if part == Task::One.name() { one } else { two };
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input).unwrap();
+    let input: Vec<String> = input.lines().map(String::from).collect();
+
+    // The process that asked for the part reports the panic instead
+    std::panic::set_hook(Box::new(|_| {}));
+    let base = IN_USE.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let t = Instant::now();
+    let res = std::panic::catch_unwind(|| solver(&input));
+    let solved = |answer: Answer| {
+        let peak = PEAK.load(Ordering::Relaxed).saturating_sub(base);
+        format!(
+            "solved {} {}\n{}",
+            t.elapsed().as_nanos(),
+            peak,
+            answer.encode()
+        )
+    };
+    let report = match res {
+        Ok(answer) => solved(answer),
+        Err(payload) => match panic_message(payload) {
+            msg if UNSOLVED_PANICS.iter().any(|p| msg.starts_with(p)) => solved(Answer::Unsolved),
+            msg => format!("panicked\n{}", msg),
+        },
+    };
+    std::fs::write(std::env::var(RESULT_VAR).unwrap(), report).unwrap();
+    let _ = std::io::stdout().flush();
+    std::process::exit(0);
+}
+
+/// Runs a part in a process of its own, which is killed if it runs past the
+/// timeout. It gets the input on stdin and otherwise the same arguments,
+/// environment and output as this process
+pub fn run(input: &[String], task: Task, timeout: Duration) -> Outcome {
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+    let result = std::env::temp_dir().join(format!(
+        "aoc-{}-{}",
+        std::process::id(),
+        RUNS.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args(std::env::args_os().skip(1))
+        .env(PART_VAR, task.name())
+        .env(RESULT_VAR, &result)
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // A part that dies before it has read all of its input closes the pipe
+    // early, which its exit status tells about already
+    let mut stdin = BufWriter::new(child.stdin.take().unwrap());
+    let _ = input
+        .iter()
+        .try_for_each(|line| writeln!(stdin, "{}", line));
+    drop(stdin);
+
+    let start = Instant::now();
+    let mut poll = Duration::from_micros(100);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break Some(status);
+        }
+        if start.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            break None;
+        }
+        std::thread::sleep(poll);
+        poll = (poll * 2).min(MAX_POLL);
+    };
+    let report = std::fs::read_to_string(&result).ok();
+    let _ = std::fs::remove_file(&result);
+    match status {
+        None => Outcome::TimedOut,
+        Some(status) => report
+            .and_then(|report| read_report(&report))
+            .unwrap_or_else(|| Outcome::Crashed(crash_reason(status))),
+    }
+}
+
+fn crash_reason(status: ExitStatus) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        let reason = match status.signal() {
+            Some(6) => Some("aborted (SIGABRT), as on a stack overflow"),
+            Some(9) => Some("killed (SIGKILL), as when out of memory"),
+            Some(11) => Some("segmentation fault (SIGSEGV)"),
+            _ => None,
+        };
+        if let Some(reason) = reason {
+            return reason.to_string();
+        }
+    }
+    status.to_string()
+}
+
+fn read_report(report: &str) -> Option<Outcome> {
+    let (head, body) = report.split_once('\n')?;
+    match head.split(' ').collect::<Vec<_>>()[..] {
+        ["solved", nanos, peak] => {
+            let usage = Usage {
+                elapsed: Duration::from_nanos(nanos.parse().ok()?),
+                peak: peak.parse().ok()?,
+            };
+            Some(Outcome::Solved(Answer::decode(body)?, usage))
+        }
+        ["panicked"] => Some(Outcome::Panicked(body.to_string())),
+        _ => None,
+    }
+}
+
+/// Runs a part for a normal run, with the timeout from `TIMEOUT`. Anything
+/// but an answer comes back as the status to show in its place
+pub fn part(input: &[String], task: Task) -> Result<(Answer, Usage), String> {
+    let timeout = timeout();
+    match run(input, task, timeout) {
+        Outcome::Solved(answer, usage) => Ok((answer, usage)),
+        outcome => Err(report(outcome, None, timeout).0),
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+fn report_json(
+    name: &str,
+    part: &str,
+    outcome: &Outcome,
+    expected: Option<&String>,
+    timeout: Duration,
+) -> String {
+    let (answer, usage, status, message) = match outcome {
+        Outcome::Solved(Answer::Unsolved, _) => (None, None, "unsolved", None),
+        Outcome::Solved(answer, usage) => {
+            let status = match expected {
+                None => "unrecorded",
+                Some(e) if answer.matches(e) => "ok",
+                Some(_) => "wrong",
+            };
+            (Some(answer), Some(usage), status, None)
+        }
+        Outcome::Panicked(msg) => (None, None, "failed", Some(msg.clone())),
+        Outcome::Crashed(reason) => (None, None, "crashed", Some(reason.clone())),
+        Outcome::TimedOut => (None, None, "timed out", Some(format!("{:?}", timeout))),
+    };
+    let null = || "null".to_string();
+    format!(
+        "{{\"input\":{},\"part\":{},\"status\":\"{}\",\"answer\":{},\"expected\":{},\"message\":{},\"ms\":{},\"memory\":{}}}",
+        json_string(name),
+        json_string(part),
+        status,
+        answer.map_or_else(null, Answer::to_json),
+        expected.map_or_else(null, |e| json_string(e.trim())),
+        message.map_or_else(null, |m| json_string(&m)),
+        usage.map_or_else(null, |u| u.elapsed.as_millis().to_string()),
+        usage.map_or_else(null, |u| u.peak.to_string()),
+    )
+}
+
+fn report(outcome: Outcome, expected: Option<&String>, timeout: Duration) -> (String, bool) {
+    match outcome {
+        Outcome::Solved(Answer::Unsolved, _) => ("unsolved".to_string(), false),
+        Outcome::Solved(answer, usage) => {
+            let res = format!(
+                "{} ({}ms, {})",
+                answer,
+                usage.elapsed.as_millis(),
+                usage.memory()
+            );
+            match expected {
+                None => (format!("{}\tunrecorded", res), false),
+                Some(e) if answer.matches(e) => (format!("{}\tok", res), false),
+                Some(e) => (format!("{}\texpected {}", res, e.trim()), true),
+            }
+        }
+        Outcome::Panicked(msg) => (format!("failed: {}", msg), true),
+        Outcome::Crashed(reason) => (format!("crashed: {}", reason), true),
+        Outcome::TimedOut => (format!("timed out after {:?}", timeout), true),
+    }
+}